@@ -9,6 +9,8 @@ criterion_main!(
     sampling::sampling,
     fft::fft,
     modulation::modulation,
+    simd::simd,
+    parallel::parallel,
     experiment_downsample
 );
 
@@ -196,7 +198,7 @@ mod modulation {
 
     fn random_bits(n: usize) -> Vec<u8> {
         let mut r = thread_rng();
-        (0..n).map(|_| r.gen_range(0u8, 1u8)).collect::<Vec<_>>()
+        (0..n).map(|_| r.gen_range(0u8, 2u8)).collect::<Vec<_>>()
     }
 
     fn random_symbols(n: usize) -> Vec<cf32> {
@@ -205,7 +207,7 @@ mod modulation {
         (0..n).map(cplx).collect::<Vec<_>>()
     }
 
-    use aether_primitives::modulation::{bpsk, qpsk, Modulation};
+    use aether_primitives::modulation::{bpsk, qam64, qpsk, Modulation, QamMapping, Slicer};
     fn modulate(c: &mut Criterion) {
         c.bench_function_over_inputs(
             "modulation modulate qpsk",
@@ -277,6 +279,60 @@ mod modulation {
             },
             vec![100usize, 500, 1000, 4000, 8000],
         );
+
+        c.bench_function_over_inputs(
+            "modulation demod qam64",
+            |b: &mut criterion::Bencher, nsymbs: &usize| {
+                b.iter_with_setup(
+                    || {
+                        let m = qam64(QamMapping::ThreeGpp);
+                        let src = random_symbols(*nsymbs);
+                        let dst = Vec::with_capacity(*nsymbs * m.bits_per_symbol());
+                        (m, src, dst)
+                    },
+                    |(m, src, mut dst)| {
+                        m.demod_naive(&mut src.iter(), &mut dst);
+                    },
+                );
+            },
+            vec![100usize, 500, 1000, 4000, 8000],
+        );
+
+        c.bench_function_over_inputs(
+            "modulation slicer qam64",
+            |b: &mut criterion::Bencher, nsymbs: &usize| {
+                b.iter_with_setup(
+                    || {
+                        let s = Slicer::qam(6, QamMapping::ThreeGpp);
+                        let src = random_symbols(*nsymbs);
+                        let dst = Vec::with_capacity(*nsymbs * s.bits_per_symbol());
+                        (s, src, dst)
+                    },
+                    |(s, src, mut dst)| {
+                        s.demod(&mut src.iter(), &mut dst);
+                    },
+                );
+            },
+            vec![100usize, 500, 1000, 4000, 8000],
+        );
+
+        c.bench_function_over_inputs(
+            "modulation slicer qpsk",
+            |b: &mut criterion::Bencher, nsymbs: &usize| {
+                b.iter_with_setup(
+                    || {
+                        let s = Slicer::qam(2, QamMapping::Gray);
+                        let src = random_symbols(*nsymbs);
+                        let dst = Vec::with_capacity(*nsymbs * s.bits_per_symbol());
+                        (s, src, dst)
+                    },
+                    |(s, src, mut dst)| {
+                        s.demod(&mut src.iter(), &mut dst);
+                    },
+                );
+            },
+            vec![100usize, 500, 1000, 4000, 8000],
+        );
     }
 }
 
@@ -422,3 +478,140 @@ mod fft {
         }
     }
 }
+
+mod simd {
+    use super::prelude::*;
+
+    criterion_group!(simd, simd_vs_iter);
+
+    /// compares the explicitly vectorised operations against their VecOps counterparts
+    pub fn simd_vs_iter(_c: &mut Criterion) {
+        #[cfg(feature = "simd")]
+        {
+            use aether_primitives::simd;
+
+            let make_vecs = || {
+                let v = vec![cf32::new(1.0, 1.0); 2048];
+                let v2 = vec![cf32::new(1.0, 1.0); 2048];
+                (v, v2)
+            };
+
+            _c.bench_function("VecOps.vec_mul", move |b| {
+                b.iter_with_setup(make_vecs, |(mut v, v2)| {
+                    v.vec_mul(&v2);
+                    black_box(v)
+                });
+            });
+            _c.bench_function("simd mul", move |b| {
+                b.iter_with_setup(make_vecs, |(mut v, v2)| {
+                    simd::mul(&mut v, &v2);
+                    black_box(v)
+                });
+            });
+
+            _c.bench_function("VecOps.vec_add", move |b| {
+                b.iter_with_setup(make_vecs, |(mut v, v2)| {
+                    v.vec_add(&v2);
+                    black_box(v)
+                });
+            });
+            _c.bench_function("simd add", move |b| {
+                b.iter_with_setup(make_vecs, |(mut v, v2)| {
+                    simd::add(&mut v, &v2);
+                    black_box(v)
+                });
+            });
+
+            _c.bench_function("VecOps.vec_scale", move |b| {
+                b.iter_with_setup(make_vecs, |(mut v, _)| {
+                    v.vec_scale(2.0);
+                    black_box(v)
+                });
+            });
+            _c.bench_function("simd scale", move |b| {
+                b.iter_with_setup(make_vecs, |(mut v, _)| {
+                    simd::scale(&mut v, 2.0);
+                    black_box(v)
+                });
+            });
+
+            _c.bench_function("VecOps.vec_conj", move |b| {
+                b.iter_with_setup(make_vecs, |(mut v, _)| {
+                    v.vec_conj();
+                    black_box(v)
+                });
+            });
+            _c.bench_function("simd conj", move |b| {
+                b.iter_with_setup(make_vecs, |(mut v, _)| {
+                    simd::conj(&mut v);
+                    black_box(v)
+                });
+            });
+
+            _c.bench_function("iterator magnitude", move |b| {
+                b.iter_with_setup(
+                    || (make_vecs().0, vec![0f32; 2048]),
+                    |(v, mut m)| {
+                        m.iter_mut().zip(v.iter()).for_each(|(m, c)| *m = c.norm());
+                        black_box(m)
+                    },
+                );
+            });
+            _c.bench_function("simd magnitude", move |b| {
+                b.iter_with_setup(
+                    || (make_vecs().0, vec![0f32; 2048]),
+                    |(v, mut m)| {
+                        simd::mag(&v, &mut m);
+                        black_box(m)
+                    },
+                );
+            });
+        }
+    }
+}
+
+mod parallel {
+    use super::prelude::*;
+
+    criterion_group!(parallel, parallel_vs_iter);
+
+    /// compares the multi-threaded operations against their VecOps counterparts on a large buffer
+    pub fn parallel_vs_iter(_c: &mut Criterion) {
+        #[cfg(feature = "parallel")]
+        {
+            use aether_primitives::parallel::ParVecOps;
+
+            let make_vecs = || {
+                let v = vec![cf32::new(1.0, 1.0); 1 << 20];
+                let v2 = vec![cf32::new(1.0, 1.0); 1 << 20];
+                (v, v2)
+            };
+
+            _c.bench_function("VecOps.vec_mul 1M", move |b| {
+                b.iter_with_setup(make_vecs, |(mut v, v2)| {
+                    v.vec_mul(&v2);
+                    black_box(v)
+                });
+            });
+            _c.bench_function("ParVecOps.par_vec_mul 1M", move |b| {
+                b.iter_with_setup(make_vecs, |(mut v, v2)| {
+                    v.par_vec_mul(&v2);
+                    black_box(v)
+                });
+            });
+
+            _c.bench_function("VecOps.vec_scale 1M", move |b| {
+                b.iter_with_setup(make_vecs, |(mut v, _)| {
+                    v.vec_scale(0.5);
+                    black_box(v)
+                });
+            });
+            _c.bench_function("ParVecOps.par_vec_scale 1M", move |b| {
+                b.iter_with_setup(make_vecs, |(mut v, _)| {
+                    v.par_vec_scale(0.5);
+                    black_box(v)
+                });
+            });
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use std::alloc::{self, Layout};
+use std::fmt;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::slice;
+
+/// Default alignment in bytes; 32 bytes suit AVX loads and stores
+/// (this is also what [VOLK](https://libvolk.org) prefers)
+pub const DEFAULT_ALIGN: usize = 32;
+
+/// Create an [AlignedBuf](crate::aligned::AlignedBuf) filled with the given element,
+/// similar to the ```vec!``` macro.
+/// The alignment in bytes is [DEFAULT_ALIGN](crate::aligned::DEFAULT_ALIGN) unless given as third argument.
+/// # Example
+/// ```
+/// #[macro_use]
+/// extern crate aether_primitives;
+/// use aether_primitives::cf32;
+///# fn main(){
+///
+/// let a = vec_align![cf32::default(); 1024];
+/// assert_eq!(a.len(), 1024);
+/// assert_eq!(a.as_ptr() as usize % 32, 0);
+///
+/// let b = vec_align![cf32::new(1.0, 0.0); 100; 64];
+/// assert_eq!(b.as_ptr() as usize % 64, 0);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! vec_align {
+    ($elem:expr; $len:expr) => {
+        $crate::aligned::AlignedBuf::from_elem($elem, $len, $crate::aligned::DEFAULT_ALIGN)
+    };
+
+    ($elem:expr; $len:expr; $align:expr) => {
+        $crate::aligned::AlignedBuf::from_elem($elem, $len, $align)
+    };
+}
+
+/// A heap-allocated buffer with a fixed length, whose first element is aligned to
+/// a given number of bytes (e.g. 32 for AVX or 64 for cache lines/AVX-512).
+///
+/// In contrast to a ```Vec``` it can not grow, thus the alignment is never lost to a reallocation.
+/// It derefs to a slice, so it can be handed to anything taking slices
+/// (e.g. [BinaryReader::read](crate::util::file::BinaryReader::read)) and implements
+/// [VecOps](crate::vecops::VecOps) for ```cf32```.
+///
+/// # Example
+/// ```
+/// use aether_primitives::{cf32, vecops::VecOps};
+/// use aether_primitives::aligned::AlignedBuf;
+/// use aether_primitives::pool::{self, Pool};
+///
+/// let mut buf = AlignedBuf::<cf32>::with_align(128, 64);
+/// assert!(buf.is_aligned());
+/// buf.vec_mutate(|c| c.re = 1.0).vec_scale(2.0);
+/// assert_eq!(buf[127], cf32::new(2.0, 0.0));
+///
+/// // buffers can be pooled like any other object
+/// let maker = Box::new(|| AlignedBuf::<cf32>::new(2048));
+/// let resetter = Box::new(|b: &mut AlignedBuf<cf32>| { b.vec_zero(); });
+/// let pool: Pool<AlignedBuf<cf32>> = pool::make(2, maker, resetter);
+/// let elem = pool.take().unwrap();
+/// assert_eq!(elem.len(), 2048);
+/// ```
+pub struct AlignedBuf<T: Copy> {
+    ptr: NonNull<T>,
+    len: usize,
+    layout: Layout,
+}
+
+// AlignedBuf owns its elements just like a Vec or Box<[T]>
+unsafe impl<T: Copy + Send> Send for AlignedBuf<T> {}
+unsafe impl<T: Copy + Sync> Sync for AlignedBuf<T> {}
+
+impl<T: Copy + Default> AlignedBuf<T> {
+    /// Allocate a buffer of ```len``` default elements, aligned to [DEFAULT_ALIGN](DEFAULT_ALIGN) bytes
+    pub fn new(len: usize) -> AlignedBuf<T> {
+        AlignedBuf::from_elem(T::default(), len, DEFAULT_ALIGN)
+    }
+
+    /// Allocate a buffer of ```len``` default elements, aligned to ```align``` bytes
+    /// # Panics
+    /// Panics if ```align``` is not a power of two or less than the alignment of ```T```
+    pub fn with_align(len: usize, align: usize) -> AlignedBuf<T> {
+        AlignedBuf::from_elem(T::default(), len, align)
+    }
+}
+
+impl<T: Copy> AlignedBuf<T> {
+    /// Allocate a buffer of ```len``` copies of ```elem```, aligned to ```align``` bytes
+    /// # Panics
+    /// Panics if ```align``` is not a power of two or less than the alignment of ```T```
+    pub fn from_elem(elem: T, len: usize, align: usize) -> AlignedBuf<T> {
+        let buf = AlignedBuf::<T>::alloc(len, align);
+        for i in 0..len {
+            unsafe { buf.ptr.as_ptr().add(i).write(elem) };
+        }
+        buf
+    }
+
+    /// Allocate a buffer aligned to ```align``` bytes and copy the contents of ```src``` into it
+    /// # Panics
+    /// Panics if ```align``` is not a power of two or less than the alignment of ```T```
+    pub fn from_slice(src: &[T], align: usize) -> AlignedBuf<T> {
+        let buf = AlignedBuf::<T>::alloc(src.len(), align);
+        unsafe {
+            buf.ptr
+                .as_ptr()
+                .copy_from_nonoverlapping(src.as_ptr(), src.len())
+        };
+        buf
+    }
+
+    /// Allocates the memory but does not initialise the elements
+    fn alloc(len: usize, align: usize) -> AlignedBuf<T> {
+        assert!(
+            align.is_power_of_two() && align >= mem::align_of::<T>(),
+            "Alignment must be a power of two and at least the alignment of the element type"
+        );
+        let size = len
+            .checked_mul(mem::size_of::<T>())
+            .expect("Buffer size overflows usize");
+        // zero-sized allocations are not allowed, so empty buffers still get a few bytes
+        let layout =
+            Layout::from_size_align(size.max(align), align).expect("Invalid buffer layout");
+        let ptr = unsafe { alloc::alloc(layout) } as *mut T;
+        let ptr = match NonNull::new(ptr) {
+            Some(p) => p,
+            None => alloc::handle_alloc_error(layout),
+        };
+        AlignedBuf { ptr, len, layout }
+    }
+
+    /// The alignment of this buffer in bytes
+    pub fn align(&self) -> usize {
+        self.layout.align()
+    }
+
+    /// Checks whether the start of the buffer is actually aligned to [align](AlignedBuf::align) bytes
+    pub fn is_aligned(&self) -> bool {
+        (self.ptr.as_ptr() as usize) % self.align() == 0
+    }
+}
+
+impl<T: Copy> Drop for AlignedBuf<T> {
+    fn drop(&mut self) {
+        // T: Copy, so there are no element destructors to run
+        unsafe { alloc::dealloc(self.ptr.as_ptr() as *mut u8, self.layout) }
+    }
+}
+
+impl<T: Copy> Deref for AlignedBuf<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: Copy> DerefMut for AlignedBuf<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: Copy> AsRef<[T]> for AlignedBuf<T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T: Copy> AsMut<[T]> for AlignedBuf<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T: Copy> Clone for AlignedBuf<T> {
+    fn clone(&self) -> AlignedBuf<T> {
+        AlignedBuf::from_slice(self, self.align())
+    }
+}
+
+impl<T: Copy + PartialEq> PartialEq for AlignedBuf<T> {
+    fn eq(&self, other: &AlignedBuf<T>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for AlignedBuf<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::aligned::AlignedBuf;
+    use crate::cf32;
+    use crate::vecops::VecOps;
+
+    #[test]
+    fn alignment() {
+        for align in &[8usize, 16, 32, 64, 128] {
+            for len in &[0usize, 1, 3, 1024] {
+                let b = AlignedBuf::<cf32>::with_align(*len, *align);
+                assert_eq!(b.len(), *len);
+                assert_eq!(b.align(), *align);
+                assert!(b.is_aligned(), "Buffer is not aligned to {} bytes", align);
+                assert!(b.iter().all(|c| *c == cf32::default()));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn underaligned() {
+        // cf32 needs at least 4 bytes
+        AlignedBuf::<cf32>::with_align(16, 2);
+    }
+
+    #[test]
+    fn vec_align() {
+        let a = vec_align![cf32::new(1.0, 1.0); 100];
+        assert_eq!(a.align(), 32);
+        let b = vec_align![cf32::new(1.0, 1.0); 100; 64];
+        assert_eq!(b.align(), 64);
+        assert!(b.is_aligned());
+        assert_eq!(a, b);
+
+        let c = b.clone();
+        assert_eq!(c.align(), 64);
+        assert_eq!(c[..], vec![cf32::new(1.0, 1.0); 100][..]);
+    }
+
+    #[test]
+    fn vec_ops() {
+        let mut a = vec_align![cf32::new(1.0, 1.0); 64];
+        let twos = vec_align![cf32::new(2.0, 0.0); 64];
+        a.vec_mul(&twos).vec_conj().vec_sub(&twos);
+
+        assert_evm!(&a[..], vec![cf32::new(0.0, -2.0); 64]);
+    }
+
+    // this test requires the tmpfs because we do not want files to persist
+    // across reboots (or (failed) runs for that matter) /tmp is perfect for that
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn binary_reader() {
+        use crate::util::file;
+        use std::fs;
+        use std::path::PathBuf;
+        let tmpfile = PathBuf::from("/tmp/aether_primitives_aligned_test.bin");
+        fs::remove_file(&tmpfile).unwrap_or(());
+
+        let seq = (0..200)
+            .map(|x| cf32::new(x as f32, -x as f32))
+            .collect::<Vec<_>>();
+        {
+            let mut w = file::binary_writer::<cf32>(&tmpfile).expect("failed to open for writing");
+            w.write(&seq).expect("Failed to write");
+        }
+
+        let mut r = file::binary_reader::<cf32>(&tmpfile).expect("Failed to open for reading");
+        let mut buf = AlignedBuf::<cf32>::with_align(200, 64);
+        r.read(&mut buf).expect("Failed to load");
+        assert!(buf.is_aligned());
+        assert_eq!(buf[..], seq[..], "Read data and original do not match up");
+
+        fs::remove_file(&tmpfile).expect("Failed to delete tempfile");
+    }
+}
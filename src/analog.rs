@@ -0,0 +1,457 @@
+use crate::cf32;
+use crate::fir::Fir;
+use crate::nco::Nco;
+use std::f32::consts::PI;
+
+/// Amplitude modulator producing complex baseband (the carrier at 0 Hz) from real audio.
+/// Audio is expected in ```-1..=1```.
+///
+/// # Example
+/// ```
+/// use aether_primitives::{cf32, analog::AmMod};
+///
+/// let am = AmMod::dsb(0.5);
+/// let mut out = vec![cf32::default(); 2];
+/// am.modulate(&[1.0, -1.0], &mut out);
+/// assert_eq!(out, vec![cf32::new(1.5, 0.0), cf32::new(0.5, 0.0)]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AmMod {
+    depth: f32,
+    carrier: bool,
+}
+
+impl AmMod {
+    /// Double sideband with carrier and the given modulation depth (0..=1 avoids overmodulation):
+    /// ```1 + depth * a[n]```
+    pub fn dsb(depth: f32) -> AmMod {
+        AmMod {
+            depth,
+            carrier: true,
+        }
+    }
+
+    /// Double sideband with suppressed carrier: ```a[n]```
+    pub fn dsb_sc() -> AmMod {
+        AmMod {
+            depth: 1.0,
+            carrier: false,
+        }
+    }
+
+    /// Modulate ```audio``` and write the result to ```output```
+    pub fn modulate(&self, audio: &[f32], output: &mut [cf32]) {
+        assert_eq!(audio.len(), output.len(), "Vectors must have same length");
+        let carrier = if self.carrier { 1.0 } else { 0.0 };
+        output
+            .iter_mut()
+            .zip(audio)
+            .for_each(|(o, a)| *o = cf32::new(carrier + self.depth * a, 0.0));
+    }
+}
+
+/// Envelope detector for AM with carrier: takes the magnitude of each sample and removes
+/// the carrier (DC) with a single-pole high pass. The DC estimate is kept across blocks.
+#[derive(Debug, Clone)]
+pub struct EnvelopeDemod {
+    alpha: f32,
+    dc: f32,
+}
+
+impl EnvelopeDemod {
+    /// Create an envelope detector whose DC removal has the time constant ```tau``` (seconds),
+    /// which should be well above the period of the lowest audio frequency (e.g. 0.05s)
+    pub fn new(tau: f32, sample_rate: f32) -> EnvelopeDemod {
+        EnvelopeDemod {
+            alpha: 1.0 - (-1.0 / (tau * sample_rate)).exp(),
+            dc: 0.0,
+        }
+    }
+
+    /// Demodulate ```samples``` and write the audio to ```audio```
+    pub fn demod(&mut self, samples: &[cf32], audio: &mut [f32]) {
+        assert_eq!(samples.len(), audio.len(), "Vectors must have same length");
+        for (a, s) in audio.iter_mut().zip(samples) {
+            let env = s.norm();
+            self.dc += self.alpha * (env - self.dc);
+            *a = env - self.dc;
+        }
+    }
+}
+
+/// Coefficient of the single-pole pre-/de-emphasis filter with time constant ```tau```
+fn emphasis(tau: f32, sample_rate: f32) -> f32 {
+    (-1.0 / (tau * sample_rate)).exp()
+}
+
+/// Frequency modulator with a constant envelope. The phase is kept across blocks.
+/// Narrowband FM typically uses a peak deviation of 2.5 or 5 kHz, broadcast FM 75 kHz
+/// with a pre-emphasis of 50µs (Europe) or 75µs (Americas).
+///
+/// # Example
+/// ```
+/// use aether_primitives::{cf32, analog::{FmDemod, FmMod}};
+///
+/// let audio = (0..480).map(|n| (n as f32 * 0.05).sin()).collect::<Vec<_>>();
+/// let mut samples = vec![cf32::default(); 480];
+/// FmMod::new(5000.0, 48000.0).modulate(&audio, &mut samples);
+///
+/// let mut demod = vec![0f32; 480];
+/// FmDemod::new(5000.0, 48000.0).demod(&samples, &mut demod);
+/// assert!(demod.iter().zip(&audio).all(|(d, a)| (d - a).abs() < 1e-3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FmMod {
+    /// phase change per sample for an audio amplitude of 1
+    sensitivity: f32,
+    phase: f32,
+    /// pre-emphasis coefficient and last audio sample
+    preemphasis: Option<(f32, f32)>,
+}
+
+impl FmMod {
+    /// Create a modulator with the peak ```deviation``` (Hz) for an audio amplitude of 1
+    pub fn new(deviation: f32, sample_rate: f32) -> FmMod {
+        FmMod {
+            sensitivity: 2.0 * PI * deviation / sample_rate,
+            phase: 0.0,
+            preemphasis: None,
+        }
+    }
+
+    /// Apply a pre-emphasis with the time constant ```tau``` (seconds) to the audio,
+    /// the exact inverse of [FmDemod::with_deemphasis](FmDemod::with_deemphasis)
+    pub fn with_preemphasis(mut self, tau: f32, sample_rate: f32) -> FmMod {
+        self.preemphasis = Some((emphasis(tau, sample_rate), 0.0));
+        self
+    }
+
+    /// Modulate ```audio``` and write the result to ```output```
+    pub fn modulate(&mut self, audio: &[f32], output: &mut [cf32]) {
+        assert_eq!(audio.len(), output.len(), "Vectors must have same length");
+        for (o, a) in output.iter_mut().zip(audio) {
+            let a = match &mut self.preemphasis {
+                Some((b, last)) => {
+                    let y = (a - *b * *last) / (1.0 - *b);
+                    *last = *a;
+                    y
+                }
+                None => *a,
+            };
+            self.phase = (self.phase + self.sensitivity * a) % (2.0 * PI);
+            *o = cf32::from_polar(&1.0, &self.phase);
+        }
+    }
+}
+
+/// FM demodulator based on a quadrature (atan) discriminator: the phase difference between
+/// consecutive samples, scaled so that the peak ```deviation``` results in an amplitude of 1.
+/// The last sample and the de-emphasis state are kept across blocks.
+#[derive(Debug, Clone)]
+pub struct FmDemod {
+    scale: f32,
+    last: cf32,
+    /// de-emphasis coefficient and last output
+    deemphasis: Option<(f32, f32)>,
+}
+
+impl FmDemod {
+    /// Create a demodulator for the peak ```deviation``` (Hz)
+    pub fn new(deviation: f32, sample_rate: f32) -> FmDemod {
+        FmDemod {
+            scale: sample_rate / (2.0 * PI * deviation),
+            last: cf32::new(1.0, 0.0),
+            deemphasis: None,
+        }
+    }
+
+    /// Apply a single-pole de-emphasis low pass with the time constant ```tau``` (seconds)
+    /// to the audio, e.g. 50e-6 or 75e-6 for broadcast FM
+    pub fn with_deemphasis(mut self, tau: f32, sample_rate: f32) -> FmDemod {
+        self.deemphasis = Some((emphasis(tau, sample_rate), 0.0));
+        self
+    }
+
+    /// Demodulate ```samples``` and write the audio to ```audio```
+    pub fn demod(&mut self, samples: &[cf32], audio: &mut [f32]) {
+        assert_eq!(samples.len(), audio.len(), "Vectors must have same length");
+        for (a, s) in audio.iter_mut().zip(samples) {
+            let f = (s * self.last.conj()).arg() * self.scale;
+            self.last = *s;
+            *a = match &mut self.deemphasis {
+                Some((b, y)) => {
+                    *y = (1.0 - *b) * f + *b * *y;
+                    *y
+                }
+                None => f,
+            };
+        }
+    }
+}
+
+/// Sideband of an SSB signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sideband {
+    /// Upper sideband: the audio occupies positive frequencies
+    Upper,
+    /// Lower sideband: the audio occupies negative frequencies
+    Lower,
+}
+
+/// Taps of a Hamming-windowed Hilbert transformer of odd length
+/// combined with a delay of ```(len - 1) / 2``` on the in-phase part:
+/// ```delta[n - d] + j * h[n]``` for the upper and ```delta[n - d] - j * h[n]``` for the lower sideband.
+fn phasing_taps(len: usize, sideband: Sideband) -> Vec<cf32> {
+    let d = (len / 2) as isize;
+    let sign = match sideband {
+        Sideband::Upper => 1.0,
+        Sideband::Lower => -1.0,
+    };
+    (0..len as isize)
+        .map(|n| {
+            let k = n - d;
+            if k == 0 {
+                return cf32::new(1.0, 0.0);
+            }
+            let window = 0.54 + 0.46 * (PI * k as f32 / d as f32).cos();
+            let h = if k % 2 == 0 {
+                0.0
+            } else {
+                2.0 / (PI * k as f32) * window
+            };
+            cf32::new(0.0, sign * h)
+        })
+        .collect()
+}
+
+/// Single sideband modulator and phasing demodulator using a Hilbert transformer.
+/// The modulator produces the analytic signal ```a + j*H{a}``` (upper sideband) or its conjugate
+/// (lower sideband), the demodulator computes ```I - H{Q}``` (upper) or ```I + H{Q}``` (lower),
+/// rejecting the opposite sideband.
+/// Both directions are delayed by ```(taps - 1) / 2``` samples and keep their filter state
+/// across blocks.
+///
+/// # Example
+/// ```
+/// use aether_primitives::{cf32, analog::{Sideband, Ssb}};
+///
+/// let audio = (0..200).map(|n| (n as f32 * 0.8).sin()).collect::<Vec<_>>();
+/// let mut samples = vec![cf32::default(); 200];
+/// let mut usb = Ssb::new(Sideband::Upper, 63);
+/// usb.modulate(&audio, &mut samples);
+///
+/// // a lower sideband receiver hardly hears anything
+/// let mut demod = vec![0f32; 200];
+/// Ssb::new(Sideband::Lower, 63).demod(&samples, &mut demod);
+/// assert!(demod[100..].iter().all(|a| a.abs() < 0.1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ssb {
+    sideband: Sideband,
+    modulator: Fir<cf32>,
+    demodulator: Fir<cf32>,
+    tmp: Vec<cf32>,
+}
+
+impl Ssb {
+    /// Create an SSB modem with a Hilbert transformer of ```taps``` taps
+    /// More taps improve the sideband rejection at low audio frequencies.
+    /// # Panics
+    /// Panics if ```taps``` is even or less than 3
+    pub fn new(sideband: Sideband, taps: usize) -> Ssb {
+        assert!(
+            taps >= 3 && taps % 2 == 1,
+            "The Hilbert transformer needs an odd number of taps"
+        );
+        let t = phasing_taps(taps, sideband);
+        Ssb {
+            sideband,
+            modulator: Fir::new(t.clone(), 0),
+            demodulator: Fir::new(t, 0),
+            tmp: Vec::new(),
+        }
+    }
+
+    /// The sideband of this modem
+    pub fn sideband(&self) -> Sideband {
+        self.sideband
+    }
+
+    /// Modulate ```audio``` and write the result to ```output```
+    pub fn modulate(&mut self, audio: &[f32], output: &mut [cf32]) {
+        assert_eq!(audio.len(), output.len(), "Vectors must have same length");
+        self.tmp.clear();
+        self.tmp.extend(audio.iter().map(|a| cf32::new(*a, 0.0)));
+        self.modulator.process(&self.tmp, output);
+    }
+
+    /// Demodulate ```samples``` and write the audio to ```audio```
+    pub fn demod(&mut self, samples: &[cf32], audio: &mut [f32]) {
+        assert_eq!(samples.len(), audio.len(), "Vectors must have same length");
+        self.tmp.resize(samples.len(), cf32::default());
+        self.demodulator.process(samples, &mut self.tmp);
+        // the taps yield I - H{Q} (upper) or I + H{Q} (lower) in the real part, twice the audio
+        audio
+            .iter_mut()
+            .zip(&self.tmp)
+            .for_each(|(a, y)| *a = 0.5 * y.re);
+    }
+}
+
+/// Product detector: mixes the signal with a beat frequency oscillator (BFO) and takes the
+/// real part. Demodulates DSB-SC, SSB (without rejection of the opposite sideband) and CW.
+/// The BFO phase is kept across blocks.
+#[derive(Debug, Clone)]
+pub struct ProductDetector {
+    bfo: Nco,
+    tmp: Vec<cf32>,
+}
+
+impl ProductDetector {
+    /// Create a detector whose BFO runs at ```freq``` (Hz, may be negative), e.g. the negated
+    /// offset of the carrier from 0 Hz or the desired tone frequency for CW
+    pub fn new(freq: f32, sample_rate: f32) -> ProductDetector {
+        ProductDetector {
+            bfo: Nco::new(freq, sample_rate),
+            tmp: Vec::new(),
+        }
+    }
+
+    /// Change the BFO frequency while keeping its phase
+    pub fn set_freq(&mut self, freq: f32, sample_rate: f32) {
+        self.bfo.set_freq(freq, sample_rate);
+    }
+
+    /// Demodulate ```samples``` and write the audio to ```audio```
+    pub fn demod(&mut self, samples: &[cf32], audio: &mut [f32]) {
+        assert_eq!(samples.len(), audio.len(), "Vectors must have same length");
+        self.tmp.clear();
+        self.tmp.extend_from_slice(samples);
+        self.bfo.mix(&mut self.tmp);
+        audio.iter_mut().zip(&self.tmp).for_each(|(a, y)| *a = y.re);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analog::{AmMod, EnvelopeDemod, FmDemod, FmMod, ProductDetector, Sideband, Ssb};
+    use crate::cf32;
+    use crate::nco::Nco;
+    use assert_approx_eq::assert_approx_eq;
+    use std::f32::consts::PI;
+
+    const FS: f32 = 8000.0;
+
+    fn tone(freq: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|n| (2.0 * PI * freq * n as f32 / FS).sin())
+            .collect()
+    }
+
+    /// power of the complex tone at freq contained in the signal
+    fn tone_power(signal: &[cf32], freq: f32) -> f32 {
+        let mut nco = Nco::new(-freq, FS);
+        let mut mixed = signal.to_vec();
+        nco.mix(&mut mixed);
+        (mixed.iter().sum::<cf32>() / signal.len() as f32).norm_sqr()
+    }
+
+    #[test]
+    fn am() {
+        let audio = tone(500.0, 8000);
+        let mut samples = vec![cf32::default(); 8000];
+        AmMod::dsb(0.8).modulate(&audio, &mut samples);
+        assert_approx_eq!(samples[0].re, 1.0);
+
+        let mut demod = EnvelopeDemod::new(0.05, FS);
+        let mut out = vec![0f32; 8000];
+        for (s, o) in samples.chunks(100).zip(out.chunks_mut(100)) {
+            demod.demod(s, o);
+        }
+        // after the DC estimate settled
+        for (o, a) in out[4000..].iter().zip(&audio[4000..]) {
+            assert_approx_eq!(o, 0.8 * a, 0.02);
+        }
+
+        AmMod::dsb_sc().modulate(&audio, &mut samples);
+        let mut out = vec![0f32; 8000];
+        ProductDetector::new(0.0, FS).demod(&samples, &mut out);
+        assert_eq!(out, audio);
+    }
+
+    #[test]
+    fn fm() {
+        let audio = tone(1000.0, 1000)
+            .iter()
+            .zip(tone(300.0, 1000))
+            .map(|(a, b)| 0.5 * (a + b))
+            .collect::<Vec<_>>();
+        let mut samples = vec![cf32::default(); 1000];
+        let mut modulator = FmMod::new(3000.0, FS).with_preemphasis(75e-6, FS);
+        let mut demodulator = FmDemod::new(3000.0, FS).with_deemphasis(75e-6, FS);
+
+        let mut out = vec![0f32; 1000];
+        for ((a, s), o) in audio
+            .chunks(64)
+            .zip(samples.chunks_mut(64))
+            .zip(out.chunks_mut(64))
+        {
+            modulator.modulate(a, s);
+            demodulator.demod(s, o);
+        }
+        assert!(samples.iter().all(|s| (s.norm() - 1.0).abs() < 1e-5));
+        // de-emphasis undoes the pre-emphasis
+        for (o, a) in out.iter().zip(&audio) {
+            assert_approx_eq!(o, a, 1e-3);
+        }
+
+        // the instantaneous frequency follows the audio
+        let mut samples = vec![cf32::default(); 3];
+        FmMod::new(2000.0, FS).modulate(&[1.0, -0.5, 0.0], &mut samples);
+        assert_approx_eq!(samples[0].arg(), 2.0 * PI * 2000.0 / FS);
+        assert_approx_eq!(samples[1].arg(), PI / 4.0);
+        assert_approx_eq!(samples[2].arg(), PI / 4.0);
+    }
+
+    #[test]
+    fn ssb() {
+        let audio = tone(1000.0, 2000);
+        for (sideband, sign) in [(Sideband::Upper, 1.0), (Sideband::Lower, -1.0)].iter() {
+            let mut ssb = Ssb::new(*sideband, 63);
+            let mut samples = vec![cf32::default(); 2000];
+            for (a, s) in audio.chunks(77).zip(samples.chunks_mut(77)) {
+                ssb.modulate(a, s);
+            }
+            // the tone sits on one side only
+            let wanted = tone_power(&samples[100..], sign * 1000.0);
+            let unwanted = tone_power(&samples[100..], -sign * 1000.0);
+            assert_approx_eq!(wanted, 1.0, 0.01);
+            assert!(10.0 * (unwanted / wanted).log10() < -40.0);
+
+            // round trip, delayed twice by the Hilbert transformer
+            let mut out = vec![0f32; 2000];
+            ssb.demod(&samples, &mut out);
+            for (o, a) in out[100..].iter().zip(&audio[100 - 62..]) {
+                assert_approx_eq!(o, a, 0.02);
+            }
+        }
+    }
+
+    #[test]
+    fn product_detector() {
+        // USB tone at 1 kHz received 200 Hz too high
+        let audio = tone(1000.0, 800);
+        let mut samples = vec![cf32::default(); 800];
+        Ssb::new(Sideband::Upper, 63).modulate(&audio, &mut samples);
+        Nco::new(200.0, FS).mix(&mut samples);
+
+        let mut detector = ProductDetector::new(-200.0, FS);
+        let mut out = vec![0f32; 800];
+        for (s, o) in samples.chunks(50).zip(out.chunks_mut(50)) {
+            detector.demod(s, o);
+        }
+        for (o, a) in out[100..].iter().zip(&audio[100 - 31..]) {
+            assert_approx_eq!(o, a, 0.02);
+        }
+    }
+}
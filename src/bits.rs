@@ -0,0 +1,199 @@
+/// Order in which the bits of a byte are transmitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder {
+    /// Most significant bit first (network order, most protocols)
+    MsbFirst,
+    /// Least significant bit first (e.g. UARTs, IEEE 802.15.4)
+    LsbFirst,
+}
+
+impl BitOrder {
+    /// Bit ```i``` (```0..8```) of ```byte``` in transmission order
+    #[inline(always)]
+    pub fn bit(self, byte: u8, i: usize) -> u8 {
+        match self {
+            BitOrder::MsbFirst => byte >> (7 - i) & 1,
+            BitOrder::LsbFirst => byte >> i & 1,
+        }
+    }
+}
+
+/// Unpack bytes into one bit per ```u8``` (```0``` or ```1```) as expected by
+/// [Modulation::modulate](crate::modulation::Modulation::modulate)
+///
+/// # Example
+/// ```
+/// use aether_primitives::bits::{self, BitOrder};
+///
+/// assert_eq!(bits::unpack(&[0xA1], BitOrder::MsbFirst), vec![1, 0, 1, 0, 0, 0, 0, 1]);
+/// assert_eq!(bits::unpack(&[0xA1], BitOrder::LsbFirst), vec![1, 0, 0, 0, 0, 1, 0, 1]);
+/// ```
+pub fn unpack(bytes: &[u8], order: BitOrder) -> Vec<u8> {
+    let mut bits = Vec::with_capacity(bytes.len() * 8);
+    unpack_into(bytes, order, &mut bits);
+    bits
+}
+
+/// Unpack bytes into one bit per ```u8```, appending them to ```output```
+pub fn unpack_into(bytes: &[u8], order: BitOrder, output: &mut Vec<u8>) {
+    output.extend(
+        bytes
+            .iter()
+            .flat_map(|byte| (0..8).map(move |i| order.bit(*byte, i))),
+    );
+}
+
+/// Pack one bit per ```u8``` into bytes (only the least significant bit of each input is used)
+/// A trailing incomplete byte is padded with zeros.
+///
+/// # Example
+/// ```
+/// use aether_primitives::bits::{self, BitOrder};
+///
+/// assert_eq!(bits::pack(&[1, 0, 1, 0, 0, 0, 0, 1, 1], BitOrder::MsbFirst), vec![0xA1, 0x80]);
+/// ```
+pub fn pack(bits: &[u8], order: BitOrder) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(bits.len().div_ceil(8));
+    pack_into(bits, order, &mut bytes);
+    bytes
+}
+
+/// Pack one bit per ```u8``` into bytes, appending them to ```output```
+/// A trailing incomplete byte is padded with zeros.
+pub fn pack_into(bits: &[u8], order: BitOrder, output: &mut Vec<u8>) {
+    output.extend(bits.chunks(8).map(|chunk| {
+        chunk
+            .iter()
+            .enumerate()
+            .map(|(i, b)| match order {
+                BitOrder::MsbFirst => (b & 1) << (7 - i),
+                BitOrder::LsbFirst => (b & 1) << i,
+            })
+            .fold(0, |acc, b| acc | b)
+    }));
+}
+
+/// Read-only view of packed bytes as a sequence of bits without unpacking them
+///
+/// # Example
+/// ```
+/// use aether_primitives::bits::{BitOrder, BitSlice};
+///
+/// let bytes = [0xF0, 0x0F];
+/// let bits = BitSlice::new(&bytes, BitOrder::MsbFirst);
+/// assert_eq!(bits.len(), 16);
+/// assert_eq!(bits.get(3), 1);
+/// assert_eq!(bits.get(4), 0);
+///
+/// // a view of bits 6..10
+/// let sub = bits.slice(6, 4);
+/// assert_eq!(sub.iter().collect::<Vec<_>>(), vec![0, 0, 0, 0]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BitSlice<'a> {
+    bytes: &'a [u8],
+    order: BitOrder,
+    /// first bit of the view
+    start: usize,
+    len: usize,
+}
+
+impl<'a> BitSlice<'a> {
+    /// View all bits of ```bytes```
+    pub fn new(bytes: &'a [u8], order: BitOrder) -> BitSlice<'a> {
+        BitSlice {
+            bytes,
+            order,
+            start: 0,
+            len: bytes.len() * 8,
+        }
+    }
+
+    /// Number of bits
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the view contains no bits
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bit ```i``` of this view (```0``` or ```1```)
+    /// # Panics
+    /// Panics if ```i``` is out of range
+    #[inline]
+    pub fn get(&self, i: usize) -> u8 {
+        assert!(i < self.len, "Bit index out of range");
+        let pos = self.start + i;
+        self.order.bit(self.bytes[pos / 8], pos % 8)
+    }
+
+    /// A view of ```len``` bits starting at bit ```start``` of this view
+    /// # Panics
+    /// Panics if the range exceeds this view
+    pub fn slice(&self, start: usize, len: usize) -> BitSlice<'a> {
+        assert!(start + len <= self.len, "Bit range out of range");
+        BitSlice {
+            bytes: self.bytes,
+            order: self.order,
+            start: self.start + start,
+            len,
+        }
+    }
+
+    /// Iterate over the bits
+    pub fn iter(&self) -> impl Iterator<Item = u8> + 'a {
+        let s = *self;
+        (0..s.len).map(move |i| s.get(i))
+    }
+
+    /// Unpack the bits of this view into one bit per ```u8```
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bits::{self, BitOrder, BitSlice};
+
+    #[test]
+    fn round_trip() {
+        let bytes = (0..=255u8).collect::<Vec<_>>();
+        for order in &[BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            let unpacked = bits::unpack(&bytes, *order);
+            assert_eq!(unpacked.len(), 256 * 8);
+            assert!(unpacked.iter().all(|b| *b <= 1));
+            assert_eq!(bits::pack(&unpacked, *order), bytes);
+            assert_eq!(BitSlice::new(&bytes, *order).to_vec(), unpacked);
+        }
+
+        // the orders mirror each other
+        let msb = bits::unpack(&[0x35], BitOrder::MsbFirst);
+        let mut lsb = bits::unpack(&[0x35], BitOrder::LsbFirst);
+        lsb.reverse();
+        assert_eq!(msb, lsb);
+    }
+
+    #[test]
+    fn padding() {
+        assert_eq!(bits::pack(&[1, 1, 0], BitOrder::MsbFirst), vec![0xC0]);
+        assert_eq!(bits::pack(&[1, 1, 0], BitOrder::LsbFirst), vec![0x03]);
+        // only the lsb of each input counts
+        assert_eq!(bits::pack(&[3, 2], BitOrder::LsbFirst), vec![0x01]);
+        assert!(bits::pack(&[], BitOrder::LsbFirst).is_empty());
+    }
+
+    #[test]
+    fn slice() {
+        let bytes = [0x12, 0x34, 0x56];
+        let all = bits::unpack(&bytes, BitOrder::LsbFirst);
+        let view = BitSlice::new(&bytes, BitOrder::LsbFirst);
+        let sub = view.slice(5, 13);
+        assert_eq!(sub.to_vec(), all[5..18].to_vec());
+        let subsub = sub.slice(2, 4);
+        assert_eq!(subsub.to_vec(), all[7..11].to_vec());
+        assert!(view.slice(24, 0).is_empty());
+    }
+}
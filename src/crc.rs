@@ -0,0 +1,471 @@
+use crate::util::low_bits;
+
+/// CRC parameters in the Rocksoft model
+///
+/// ```poly``` is given without the leading ```x^width``` term in normal (MSB-first) notation,
+/// ```init``` and ```xorout``` are given unreflected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    /// Number of bits of the CRC (```1..=64```)
+    pub width: u8,
+    /// Generator polynomial
+    pub poly: u64,
+    /// Initial register value
+    pub init: u64,
+    /// Whether the bits of each input byte are processed LSB-first
+    pub refin: bool,
+    /// Whether the register is reflected before the final XOR
+    pub refout: bool,
+    /// Value XORed to the register at the end
+    pub xorout: u64,
+}
+
+/// CRC-8/SMBUS: ```x^8 + x^2 + x + 1```
+pub const CRC8_SMBUS: Params = Params {
+    width: 8,
+    poly: 0x07,
+    init: 0,
+    refin: false,
+    refout: false,
+    xorout: 0,
+};
+
+/// 3GPP gCRC8 (TS 36.212 5.1.1, TS 38.212 5.1): ```D^8 + D^7 + D^4 + D^3 + D + 1```
+pub const CRC8: Params = Params {
+    width: 8,
+    poly: 0x9B,
+    init: 0,
+    refin: false,
+    refout: false,
+    xorout: 0,
+};
+
+/// 3GPP gCRC16 (TS 36.212 5.1.1, TS 38.212 5.1): ```D^16 + D^12 + D^5 + 1```,
+/// identical to CRC-16/XMODEM
+pub const CRC16: Params = Params {
+    width: 16,
+    poly: 0x1021,
+    init: 0,
+    refin: false,
+    refout: false,
+    xorout: 0,
+};
+
+/// CRC-16/XMODEM: ```x^16 + x^12 + x^5 + 1``` starting from zero
+pub const CRC16_XMODEM: Params = CRC16;
+
+/// CRC-16-CCITT as commonly implemented (CRC-16/CCITT-FALSE, CRC-16/IBM-3740):
+/// ```x^16 + x^12 + x^5 + 1``` starting from ```0xFFFF```
+pub const CRC16_CCITT_FALSE: Params = Params {
+    width: 16,
+    poly: 0x1021,
+    init: 0xFFFF,
+    refin: false,
+    refout: false,
+    xorout: 0,
+};
+
+/// CRC-16/KERMIT (the reflected CRC-16-CCITT of HDLC/X.25 without the final inversion)
+pub const CRC16_KERMIT: Params = Params {
+    width: 16,
+    poly: 0x1021,
+    init: 0,
+    refin: true,
+    refout: true,
+    xorout: 0,
+};
+
+/// 3GPP gCRC24A (TS 36.212 5.1.1, TS 38.212 5.1)
+pub const CRC24A: Params = Params {
+    width: 24,
+    poly: 0x86_4CFB,
+    init: 0,
+    refin: false,
+    refout: false,
+    xorout: 0,
+};
+
+/// 3GPP gCRC24B (TS 36.212 5.1.1, TS 38.212 5.1)
+pub const CRC24B: Params = Params {
+    width: 24,
+    poly: 0x80_0063,
+    init: 0,
+    refin: false,
+    refout: false,
+    xorout: 0,
+};
+
+/// 3GPP gCRC24C (TS 38.212 5.1)
+pub const CRC24C: Params = Params {
+    width: 24,
+    poly: 0xB2_B117,
+    init: 0,
+    refin: false,
+    refout: false,
+    xorout: 0,
+};
+
+/// CRC-32 of Ethernet, zlib and PNG (CRC-32/ISO-HDLC)
+pub const CRC32: Params = Params {
+    width: 32,
+    poly: 0x04C1_1DB7,
+    init: 0xFFFF_FFFF,
+    refin: true,
+    refout: true,
+    xorout: 0xFFFF_FFFF,
+};
+
+/// reverse the lowest ```width``` bits
+fn reflect(x: u64, width: u8) -> u64 {
+    x.reverse_bits() >> (64 - u32::from(width))
+}
+
+/// Table-driven CRC engine
+///
+/// Bytes are processed through a 256 entry table, one bit per ```u8```
+/// (as produced by [bits::unpack](crate::bits::unpack)) bit by bit in the given order.
+/// For a CRC with ```refin``` the bits of a byte are sent LSB-first, so
+/// ```checksum(bytes)``` equals ```checksum_bits(unpack(bytes, LsbFirst))```,
+/// otherwise it equals ```checksum_bits(unpack(bytes, MsbFirst))```.
+///
+/// # Example
+/// ```
+/// use aether_primitives::bits::{self, BitOrder};
+/// use aether_primitives::crc::{self, Crc};
+///
+/// let crc32 = Crc::new(crc::CRC32);
+/// assert_eq!(crc32.checksum(b"123456789"), 0xCBF4_3926);
+///
+/// // a transport block with its 3GPP CRC attached
+/// let crc24a = Crc::new(crc::CRC24A);
+/// let mut block = bits::unpack(b"123456789", BitOrder::MsbFirst);
+/// crc24a.append_bits(&mut block);
+/// assert_eq!(block.len(), 72 + 24);
+/// assert!(crc24a.check_bits(&block));
+/// block[5] ^= 1;
+/// assert!(!crc24a.check_bits(&block));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Crc {
+    params: Params,
+    /// left shift which aligns the register of CRCs narrower than 8 bits to a byte
+    shift: u8,
+    table: Vec<u64>,
+}
+
+impl Crc {
+    /// Create an engine and build its table
+    /// # Panics
+    /// Panics if the width is not in ```1..=64```
+    pub fn new(params: Params) -> Crc {
+        assert!(
+            (1..=64).contains(&params.width),
+            "The width must be in 1..=64"
+        );
+        let width = params.width;
+        let poly = params.poly & low_bits(usize::from(width));
+        let shift = 8u8.saturating_sub(width);
+
+        let table = (0..256u64)
+            .map(|byte| {
+                if params.refin {
+                    let poly = reflect(poly, width);
+                    (0..8).fold(
+                        byte,
+                        |r, _| if r & 1 == 1 { (r >> 1) ^ poly } else { r >> 1 },
+                    )
+                } else {
+                    // register of max(width, 8) bits
+                    let w = width + shift;
+                    let top = 1 << (w - 1);
+                    let m = low_bits(usize::from(w));
+                    let poly = poly << shift;
+                    (0..8).fold(byte << (w - 8), |r, _| {
+                        if r & top != 0 {
+                            ((r << 1) ^ poly) & m
+                        } else {
+                            (r << 1) & m
+                        }
+                    })
+                }
+            })
+            .collect();
+
+        Crc {
+            params,
+            shift,
+            table,
+        }
+    }
+
+    /// The parameters of this engine
+    pub fn params(&self) -> Params {
+        self.params
+    }
+
+    /// Start an incremental computation
+    pub fn digest(&self) -> Digest<'_> {
+        Digest {
+            crc: self,
+            reg: self.params.init & low_bits(usize::from(self.params.width)),
+        }
+    }
+
+    /// CRC of bytes
+    pub fn checksum(&self, bytes: &[u8]) -> u64 {
+        let mut d = self.digest();
+        d.update(bytes);
+        d.finalize()
+    }
+
+    /// CRC of bits (one bit per ```u8```, only the least significant bit is used)
+    pub fn checksum_bits(&self, bits: &[u8]) -> u64 {
+        let mut d = self.digest();
+        d.update_bits(bits);
+        d.finalize()
+    }
+
+    /// Append the CRC of ```bits``` to them, the coefficient of the highest power first
+    /// (parity bits ```p0..p(L-1)``` of 3GPP TS 38.212 5.1)
+    pub fn append_bits(&self, bits: &mut Vec<u8>) {
+        let crc = self.checksum_bits(bits);
+        let width = usize::from(self.params.width);
+        bits.extend((0..width).map(|i| (crc >> (width - 1 - i)) as u8 & 1));
+    }
+
+    /// Check bits with an appended CRC as produced by [append_bits](Crc::append_bits)
+    pub fn check_bits(&self, bits: &[u8]) -> bool {
+        let width = usize::from(self.params.width);
+        if bits.len() < width {
+            return false;
+        }
+        let (data, parity) = bits.split_at(bits.len() - width);
+        let crc = parity
+            .iter()
+            .fold(0u64, |acc, b| acc << 1 | u64::from(b & 1));
+        self.checksum_bits(data) == crc
+    }
+}
+
+/// Incremental CRC computation over bytes and bits, created by [Crc::digest](Crc::digest)
+///
+/// # Example
+/// ```
+/// use aether_primitives::bits::{self, BitOrder};
+/// use aether_primitives::crc::{self, Crc};
+///
+/// let crc = Crc::new(crc::CRC16_CCITT_FALSE);
+/// let mut d = crc.digest();
+/// d.update(b"1234");
+/// // the remaining bytes as bits
+/// d.update_bits(&bits::unpack(b"56789", BitOrder::MsbFirst));
+/// assert_eq!(d.finalize(), 0x29B1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Digest<'a> {
+    crc: &'a Crc,
+    /// unreflected register
+    reg: u64,
+}
+
+impl<'a> Digest<'a> {
+    /// Process bytes
+    pub fn update(&mut self, bytes: &[u8]) {
+        let Params { width, refin, .. } = self.crc.params;
+        let table = &self.crc.table;
+        if refin {
+            let mut r = reflect(self.reg, width);
+            for b in bytes {
+                r = (r >> 8) ^ table[((r ^ u64::from(*b)) & 0xFF) as usize];
+            }
+            self.reg = reflect(r, width);
+        } else {
+            let w = width + self.crc.shift;
+            let m = low_bits(usize::from(w));
+            let mut r = self.reg << self.crc.shift;
+            for b in bytes {
+                let idx = ((r >> (w - 8)) ^ u64::from(*b)) & 0xFF;
+                r = ((r << 8) & m) ^ table[idx as usize];
+            }
+            self.reg = r >> self.crc.shift;
+        }
+    }
+
+    /// Process bits (one bit per ```u8```, only the least significant bit is used)
+    pub fn update_bits(&mut self, bits: &[u8]) {
+        let Params { width, poly, .. } = self.crc.params;
+        let top = 1 << (width - 1);
+        let m = low_bits(usize::from(width));
+        let poly = poly & m;
+        for b in bits {
+            let fb = (self.reg & top != 0) as u8 ^ (b & 1);
+            self.reg = (self.reg << 1) & m;
+            if fb == 1 {
+                self.reg ^= poly;
+            }
+        }
+    }
+
+    /// The CRC of all processed data
+    pub fn finalize(&self) -> u64 {
+        let Params {
+            width,
+            refout,
+            xorout,
+            ..
+        } = self.crc.params;
+        let reg = if refout {
+            reflect(self.reg, width)
+        } else {
+            self.reg
+        };
+        (reg ^ xorout) & low_bits(usize::from(width))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bits::{self, BitOrder};
+    use crate::crc::{self, Crc, Params};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const CHECK: &[u8] = b"123456789";
+
+    /// bit-by-bit reference of the Rocksoft model
+    fn reference(p: Params, bytes: &[u8]) -> u64 {
+        let order = if p.refin {
+            BitOrder::LsbFirst
+        } else {
+            BitOrder::MsbFirst
+        };
+        let top = 1u128 << (p.width - 1);
+        let m = (1u128 << p.width) - 1;
+        let reg = bits::unpack(bytes, order)
+            .iter()
+            .fold(p.init as u128, |r, b| {
+                let fb = (r & top != 0) ^ (*b == 1);
+                let r = (r << 1) & m;
+                if fb {
+                    r ^ p.poly as u128
+                } else {
+                    r
+                }
+            });
+        let reg = if p.refout {
+            (0..p.width).fold(0, |acc, i| acc | ((reg >> i) & 1) << (p.width - 1 - i))
+        } else {
+            reg
+        };
+        (reg as u64 ^ p.xorout) & m as u64
+    }
+
+    #[test]
+    fn check_values() {
+        // catalogue check values of "123456789"
+        let presets = [
+            (crc::CRC8_SMBUS, 0xF4),
+            (crc::CRC8, 0xEA),
+            (crc::CRC16_XMODEM, 0x31C3),
+            (crc::CRC16_CCITT_FALSE, 0x29B1),
+            (crc::CRC16_KERMIT, 0x2189),
+            (crc::CRC24A, 0xCD_E703),
+            (crc::CRC24B, 0x23_EF52),
+            (crc::CRC32, 0xCBF4_3926),
+        ];
+        for (params, check) in presets.iter() {
+            let crc = Crc::new(*params);
+            assert_eq!(crc.checksum(CHECK), *check, "{:?}", params);
+            let order = if params.refin {
+                BitOrder::LsbFirst
+            } else {
+                BitOrder::MsbFirst
+            };
+            assert_eq!(crc.checksum_bits(&bits::unpack(CHECK, order)), *check);
+        }
+    }
+
+    #[test]
+    fn table_matches_bitwise() {
+        let mut rng = StdRng::seed_from_u64(815);
+        let data = (0..1000).map(|_| rng.gen::<u8>()).collect::<Vec<_>>();
+        let custom = [
+            crc::CRC24C,
+            // CRC-5/USB, CRC-7/MMC and CRC-64/XZ for narrow and wide registers
+            Params {
+                width: 5,
+                poly: 0x05,
+                init: 0x1F,
+                refin: true,
+                refout: true,
+                xorout: 0x1F,
+            },
+            Params {
+                width: 7,
+                poly: 0x09,
+                init: 0,
+                refin: false,
+                refout: false,
+                xorout: 0,
+            },
+            Params {
+                width: 64,
+                poly: 0x42F0_E1EB_A9EA_3693,
+                init: !0,
+                refin: true,
+                refout: true,
+                xorout: !0,
+            },
+            // CRC-12/UMTS with mixed reflection
+            Params {
+                width: 12,
+                poly: 0x80F,
+                init: 0,
+                refin: false,
+                refout: true,
+                xorout: 0,
+            },
+        ];
+        for params in custom.iter().chain([crc::CRC8, crc::CRC32].iter()) {
+            let crc = Crc::new(*params);
+            for len in &[0, 1, 9, 1000] {
+                assert_eq!(
+                    crc.checksum(&data[..*len]),
+                    reference(*params, &data[..*len]),
+                    "{:?}",
+                    params
+                );
+            }
+            // incremental in pieces
+            let mut d = crc.digest();
+            data.chunks(37).for_each(|c| d.update(c));
+            assert_eq!(d.finalize(), crc.checksum(&data));
+        }
+
+        // catalogue values of the extra widths
+        assert_eq!(Crc::new(custom[1]).checksum(CHECK), 0x19);
+        assert_eq!(Crc::new(custom[2]).checksum(CHECK), 0x75);
+        assert_eq!(Crc::new(custom[3]).checksum(CHECK), 0x995D_C9BB_DF19_39FA);
+        assert_eq!(Crc::new(custom[4]).checksum(CHECK), 0xDAF);
+    }
+
+    #[test]
+    fn attached() {
+        let mut rng = StdRng::seed_from_u64(815);
+        for params in &[crc::CRC8, crc::CRC16, crc::CRC24A, crc::CRC24B, crc::CRC24C] {
+            let crc = Crc::new(*params);
+            let mut block = (0..300)
+                .map(|_| rng.gen_range(0u8, 2u8))
+                .collect::<Vec<_>>();
+            crc.append_bits(&mut block);
+            assert!(crc.check_bits(&block));
+            // a zero remainder over data and parity (no init and xorout)
+            assert_eq!(crc.checksum_bits(&block), 0);
+            for i in (0..block.len()).step_by(17) {
+                block[i] ^= 1;
+                assert!(!crc.check_bits(&block));
+                block[i] ^= 1;
+            }
+        }
+        assert!(!Crc::new(crc::CRC24A).check_bits(&[1, 0, 1]));
+    }
+}
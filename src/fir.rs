@@ -1,22 +1,226 @@
-use std::ops::{Add, Mul};
+use crate::fixed;
+use crate::{cf32, ci16};
+use num_complex::Complex;
 
-struct Fir<T>
+/// Sample types which can be filtered by a [Fir](Fir)
+/// The products of samples and taps are summed up in an accumulator of type ```Acc```
+/// which may be wider than the sample type (e.g. for fixed-point samples).
+pub trait FirSample: Copy + Default {
+    /// accumulator for the sum of products
+    type Acc: Copy + Default;
+
+    /// add the product of sample ```x``` and ```tap``` to the accumulator
+    fn mac(acc: Self::Acc, x: Self, tap: Self) -> Self::Acc;
+
+    /// convert the accumulated sum back into a sample
+    fn finish(acc: Self::Acc) -> Self;
+}
+
+impl FirSample for f32 {
+    type Acc = f32;
+
+    #[inline(always)]
+    fn mac(acc: f32, x: f32, tap: f32) -> f32 {
+        acc + x * tap
+    }
+
+    #[inline(always)]
+    fn finish(acc: f32) -> f32 {
+        acc
+    }
+}
+
+impl FirSample for cf32 {
+    type Acc = cf32;
+
+    #[inline(always)]
+    fn mac(acc: cf32, x: cf32, tap: cf32) -> cf32 {
+        acc + x * tap
+    }
+
+    #[inline(always)]
+    fn finish(acc: cf32) -> cf32 {
+        acc
+    }
+}
+
+/// Samples and taps are Q15 numbers; the products are accumulated in Q30 with
+/// plenty of headroom and only rounded and saturated to Q15 at the end.
+impl FirSample for ci16 {
+    type Acc = Complex<i64>;
+
+    #[inline(always)]
+    fn mac(acc: Complex<i64>, x: ci16, tap: ci16) -> Complex<i64> {
+        let (xr, xi, tr, ti) = (
+            i64::from(x.re),
+            i64::from(x.im),
+            i64::from(tap.re),
+            i64::from(tap.im),
+        );
+        Complex::new(acc.re + xr * tr - xi * ti, acc.im + xr * ti + xi * tr)
+    }
+
+    #[inline(always)]
+    fn finish(acc: Complex<i64>) -> ci16 {
+        ci16::new(fixed::round_q30(acc.re), fixed::round_q30(acc.im))
+    }
+}
+
+/// A Finite Impulse Response filter computing ```y[n] = sum_k taps[k] * x[n-k]```
+/// The filter keeps the last ```taps.len() - 1``` input samples,
+/// so a continuous stream can be filtered block by block.
+///
+/// # Example
+/// ```
+/// use aether_primitives::{cf32, fir::Fir};
+///
+/// // 2-tap moving average
+/// let mut fir = Fir::new(vec![cf32::new(0.5, 0.0); 2], 4);
+/// let input = vec![cf32::new(1.0, 0.0), cf32::new(3.0, 0.0)];
+/// let mut output = vec![cf32::default(); 2];
+///
+/// fir.process(&input, &mut output);
+/// assert_eq!(output, vec![cf32::new(0.5, 0.0), cf32::new(2.0, 0.0)]);
+///
+/// // the last sample of the previous block is remembered
+/// fir.process(&input, &mut output);
+/// assert_eq!(output, vec![cf32::new(2.0, 0.0), cf32::new(2.0, 0.0)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fir<T>
 where
-    T: Mul + Add + Default,
+    T: FirSample,
 {
     taps: Vec<T>,
+    /// the last taps.len()-1 input samples followed by the current input block
     tmp: Vec<T>,
 }
 
 impl<T> Fir<T>
 where
-    T: Mul + Add + Default,
+    T: FirSample,
 {
-    fn new(taps: Vec<T>, input_len: usize) -> Fir<T> {
+    /// Create a filter with the given taps
+    /// ```input_len``` is the expected block length and is used to preallocate the internal buffer
+    /// # Panics
+    /// Panics if no taps are given
+    pub fn new(taps: Vec<T>, input_len: usize) -> Fir<T> {
+        assert!(!taps.is_empty(), "A filter needs at least one tap");
         let filter_len = taps.len() + input_len;
-        Fir {
-            taps,
-            tmp: Vec::with_capacity(filter_len),
+        let mut tmp = Vec::with_capacity(filter_len);
+        tmp.resize(taps.len() - 1, T::default());
+        Fir { taps, tmp }
+    }
+
+    /// The taps of this filter
+    pub fn taps(&self) -> &[T] {
+        &self.taps
+    }
+
+    /// Clear the remembered input samples, as if the filter was just created
+    pub fn reset(&mut self) {
+        self.tmp.iter_mut().for_each(|x| *x = T::default());
+    }
+
+    /// Filter ```input``` and write the result to ```output```
+    /// The state is carried over to the next call.
+    pub fn process(&mut self, input: &[T], output: &mut [T]) {
+        assert_eq!(input.len(), output.len(), "Vectors must have same length");
+
+        let history = self.taps.len() - 1;
+        self.tmp.extend_from_slice(input);
+
+        for (n, o) in output.iter_mut().enumerate() {
+            // tmp[n + history] holds x[n]
+            let acc = self
+                .taps
+                .iter()
+                .zip(self.tmp[n..=n + history].iter().rev())
+                .fold(T::Acc::default(), |acc, (t, x)| T::mac(acc, *x, *t));
+            *o = T::finish(acc);
         }
+
+        // only keep the samples required for the next block
+        let consumed = self.tmp.len() - history;
+        self.tmp.drain(..consumed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::fir::Fir;
+    use crate::fixed;
+    use crate::{cf32, ci16};
+
+    #[test]
+    fn impulse_response() {
+        let taps = vec![1f32, -2.0, 3.0, 0.5];
+        let mut fir = Fir::new(taps.clone(), 8);
+        let mut impulse = vec![0f32; 8];
+        impulse[1] = 1.0;
+        let mut out = vec![0f32; 8];
+        fir.process(&impulse, &mut out);
+
+        assert_eq!(out, vec![0.0, 1.0, -2.0, 3.0, 0.5, 0.0, 0.0, 0.0]);
+        assert_eq!(fir.taps(), &taps[..]);
+    }
+
+    #[test]
+    fn blockwise() {
+        let taps = (0..7)
+            .map(|i| cf32::new(1.0 / (i + 1) as f32, 0.1 * i as f32))
+            .collect::<Vec<_>>();
+        let input = (0..100)
+            .map(|i| cf32::from_polar(&1.0, &(0.3 * i as f32)))
+            .collect::<Vec<_>>();
+
+        let mut whole = vec![cf32::default(); 100];
+        Fir::new(taps.clone(), 100).process(&input, &mut whole);
+
+        // odd block lengths, some shorter than the filter
+        let mut blocks = vec![cf32::default(); 100];
+        let mut fir = Fir::new(taps.clone(), 13);
+        let mut start = 0;
+        for len in [13, 3, 1, 40, 5, 38].iter() {
+            fir.process(&input[start..start + len], &mut blocks[start..start + len]);
+            start += len;
+        }
+        assert_evm!(&blocks, &whole, -60.0);
+
+        // a reset filter behaves like a fresh one
+        fir.reset();
+        fir.process(&input, &mut blocks);
+        assert_eq!(blocks, whole);
+    }
+
+    #[test]
+    fn fixed_point() {
+        let taps_f = vec![
+            cf32::new(0.25, 0.0),
+            cf32::new(0.5, -0.125),
+            cf32::new(0.25, 0.0),
+        ];
+        let input_f = (0..64)
+            .map(|i| cf32::from_polar(&0.5, &(0.2 * i as f32)))
+            .collect::<Vec<_>>();
+        let mut correct = vec![cf32::default(); 64];
+        Fir::new(taps_f.clone(), 64).process(&input_f, &mut correct);
+
+        let mut taps = vec![ci16::default(); 3];
+        fixed::from_cf32(&taps_f, &mut taps, 1.0);
+        let mut input = vec![ci16::default(); 64];
+        fixed::from_cf32(&input_f, &mut input, 1.0);
+
+        let mut out = vec![ci16::default(); 64];
+        Fir::new(taps, 64).process(&input, &mut out);
+        let mut out_f = vec![cf32::default(); 64];
+        fixed::to_cf32(&out, &mut out_f, 1.0);
+        assert_evm!(&out_f[2..], &correct[2..], -35.0);
+
+        // full scale input through a gain > 1 saturates instead of wrapping around
+        let mut fir = Fir::new(vec![ci16::new(32_767, 0); 2], 2);
+        let mut out = vec![ci16::default(); 2];
+        fir.process(&[ci16::new(32_767, -32_768); 2], &mut out);
+        assert_eq!(out[1], ci16::new(32_767, -32_768));
     }
 }
@@ -0,0 +1,367 @@
+use crate::{cf32, ci16};
+
+/// Full scale of an i16 (sc16) sample, i.e. the magnitude of ```i16::MIN```
+const FULL_SCALE_I16: f32 = 32_768.0;
+/// Full scale of an i8 (sc8) sample, i.e. the magnitude of ```i8::MIN```
+const FULL_SCALE_I8: f32 = 128.0;
+
+/// Saturate a wide intermediate result to the i16 range
+#[inline(always)]
+fn sat(x: i64) -> i16 {
+    x.clamp(i64::from(i16::MIN), i64::from(i16::MAX)) as i16
+}
+
+/// Round a Q30 product (or a sum of them) to Q15 and saturate
+#[inline(always)]
+pub(crate) fn round_q30(x: i64) -> i16 {
+    sat((x + (1 << 14)) >> 15)
+}
+
+/// Multiply two Q15 numbers (rounding to nearest, saturating)
+/// Q15 maps -32768..=32767 to -1.0..1.0, so ```-1.0 * -1.0``` saturates to 32767
+#[inline]
+pub fn q15_mul(a: i16, b: i16) -> i16 {
+    round_q30(i64::from(a) * i64::from(b))
+}
+
+/// Multiply two complex Q15 numbers (rounding to nearest, saturating)
+#[inline]
+pub fn mul(a: ci16, b: ci16) -> ci16 {
+    let (ar, ai, br, bi) = (
+        i64::from(a.re),
+        i64::from(a.im),
+        i64::from(b.re),
+        i64::from(b.im),
+    );
+    ci16::new(round_q30(ar * br - ai * bi), round_q30(ar * bi + ai * br))
+}
+
+/// Add two complex numbers, saturating instead of wrapping around
+#[inline]
+pub fn add(a: ci16, b: ci16) -> ci16 {
+    ci16::new(a.re.saturating_add(b.re), a.im.saturating_add(b.im))
+}
+
+/// Subtract ```b``` from ```a```, saturating instead of wrapping around
+#[inline]
+pub fn sub(a: ci16, b: ci16) -> ci16 {
+    ci16::new(a.re.saturating_sub(b.re), a.im.saturating_sub(b.im))
+}
+
+/// Complex conjugate; the imaginary part of -32768 saturates to 32767
+#[inline]
+pub fn conj(a: ci16) -> ci16 {
+    ci16::new(a.re, a.im.saturating_neg())
+}
+
+/// Convert ```ci16``` samples to ```cf32```
+/// ```full_scale``` is the amplitude the fixed-point full scale (32768) maps to,
+/// i.e. with a full scale of 1.0 the samples are converted to the range -1.0..1.0
+pub fn to_cf32(src: &[ci16], dst: &mut [cf32], full_scale: f32) {
+    assert_eq!(src.len(), dst.len(), "Vectors must have same length");
+    let scale = full_scale / FULL_SCALE_I16;
+    dst.iter_mut()
+        .zip(src)
+        .for_each(|(d, s)| *d = cf32::new(f32::from(s.re), f32::from(s.im)).scale(scale));
+}
+
+/// Convert ```cf32``` samples to ```ci16```
+/// ```full_scale``` is the amplitude mapped to the fixed-point full scale (32768).
+/// Values are rounded to nearest and saturated if they exceed the full scale.
+pub fn from_cf32(src: &[cf32], dst: &mut [ci16], full_scale: f32) {
+    assert_eq!(src.len(), dst.len(), "Vectors must have same length");
+    let scale = FULL_SCALE_I16 / full_scale;
+    // float to int casts saturate (and map NaN to 0)
+    dst.iter_mut().zip(src).for_each(|(d, s)| {
+        *d = ci16::new((s.re * scale).round() as i16, (s.im * scale).round() as i16)
+    });
+}
+
+/// Convert interleaved i16 samples (I, Q, I, Q, ...) as delivered by most SDR front-ends
+/// to ```cf32``` (see [to_cf32](to_cf32) for the meaning of ```full_scale```)
+pub fn sc16_to_cf32(src: &[i16], dst: &mut [cf32], full_scale: f32) {
+    assert_eq!(
+        src.len(),
+        2 * dst.len(),
+        "Interleaved input must have twice the length of the output"
+    );
+    let scale = full_scale / FULL_SCALE_I16;
+    dst.iter_mut()
+        .zip(src.chunks_exact(2))
+        .for_each(|(d, s)| *d = cf32::new(f32::from(s[0]), f32::from(s[1])).scale(scale));
+}
+
+/// Convert interleaved i8 samples (I, Q, I, Q, ...) to ```cf32```
+/// ```full_scale``` is the amplitude the i8 full scale (128) maps to
+pub fn sc8_to_cf32(src: &[i8], dst: &mut [cf32], full_scale: f32) {
+    assert_eq!(
+        src.len(),
+        2 * dst.len(),
+        "Interleaved input must have twice the length of the output"
+    );
+    let scale = full_scale / FULL_SCALE_I8;
+    dst.iter_mut()
+        .zip(src.chunks_exact(2))
+        .for_each(|(d, s)| *d = cf32::new(f32::from(s[0]), f32::from(s[1])).scale(scale));
+}
+
+/// Convert ```cf32``` samples to interleaved i16 (I, Q, I, Q, ...)
+/// rounding and saturating like [from_cf32](from_cf32)
+pub fn cf32_to_sc16(src: &[cf32], dst: &mut [i16], full_scale: f32) {
+    assert_eq!(
+        2 * src.len(),
+        dst.len(),
+        "Interleaved output must have twice the length of the input"
+    );
+    let scale = FULL_SCALE_I16 / full_scale;
+    dst.chunks_exact_mut(2).zip(src).for_each(|(d, s)| {
+        d[0] = (s.re * scale).round() as i16;
+        d[1] = (s.im * scale).round() as i16;
+    });
+}
+
+/// Convert interleaved i16 samples (I, Q, I, Q, ...) to ```ci16``` without rescaling
+pub fn sc16_to_ci16(src: &[i16], dst: &mut [ci16]) {
+    assert_eq!(
+        src.len(),
+        2 * dst.len(),
+        "Interleaved input must have twice the length of the output"
+    );
+    dst.iter_mut()
+        .zip(src.chunks_exact(2))
+        .for_each(|(d, s)| *d = ci16::new(s[0], s[1]));
+}
+
+/// Fixed-point counterpart of [VecOps](crate::vecops::VecOps) for vectors and slices of ```ci16```.
+/// Samples are treated as Q15 numbers; all operations saturate instead of wrapping around.
+///
+/// # Example
+/// ```
+/// use aether_primitives::{ci16, fixed::FixedVecOps};
+///
+/// let half = ci16::new(16_384, 0);
+/// let mut v = vec![ci16::new(30_000, -8_000); 4];
+///
+/// v.vec_add(&vec![ci16::new(10_000, 0); 4]) // saturates at 32767
+///     .vec_mul(&vec![half; 4])
+///     .vec_conj();
+///
+/// assert_eq!(v[0], ci16::new(16_384, 4_000));
+/// ```
+pub trait FixedVecOps {
+    /// multiply each element by the Q15 scalar ```scale```
+    fn vec_scale(&mut self, scale: i16) -> &mut Self;
+
+    /// element-wise Q15 multiply this vector with the other one
+    fn vec_mul(&mut self, other: impl AsRef<[ci16]>) -> &mut Self;
+
+    /// element-wise add the other vector to this one
+    fn vec_add(&mut self, other: impl AsRef<[ci16]>) -> &mut Self;
+
+    /// element-wise subtract the other vector from this one
+    fn vec_sub(&mut self, other: impl AsRef<[ci16]>) -> &mut Self;
+
+    /// complex conjugate each element
+    fn vec_conj(&mut self) -> &mut Self;
+
+    /// arithmetic shift each element right by ```bits``` (i.e. divide by 2^bits), rounding to nearest
+    fn vec_shr(&mut self, bits: u32) -> &mut Self;
+
+    /// copies the contents of other into self
+    fn vec_clone(&mut self, other: impl AsRef<[ci16]>) -> &mut Self;
+
+    /// zero the entire vector
+    fn vec_zero(&mut self) -> &mut Self;
+}
+
+macro_rules! impl_fixed_vec_ops {
+    ($type:ty) => {
+        impl FixedVecOps for $type {
+            fn vec_scale(&mut self, scale: i16) -> &mut Self {
+                self.iter_mut()
+                    .for_each(|c| *c = ci16::new(q15_mul(c.re, scale), q15_mul(c.im, scale)));
+                self
+            }
+
+            fn vec_mul(&mut self, other: impl AsRef<[ci16]>) -> &mut Self {
+                assert_eq!(
+                    self.len(),
+                    other.as_ref().len(),
+                    "Vectors must have same length"
+                );
+                self.iter_mut()
+                    .zip(other.as_ref())
+                    .for_each(|(a, b)| *a = mul(*a, *b));
+                self
+            }
+
+            fn vec_add(&mut self, other: impl AsRef<[ci16]>) -> &mut Self {
+                assert_eq!(
+                    self.len(),
+                    other.as_ref().len(),
+                    "Vectors must have same length"
+                );
+                self.iter_mut()
+                    .zip(other.as_ref())
+                    .for_each(|(a, b)| *a = add(*a, *b));
+                self
+            }
+
+            fn vec_sub(&mut self, other: impl AsRef<[ci16]>) -> &mut Self {
+                assert_eq!(
+                    self.len(),
+                    other.as_ref().len(),
+                    "Vectors must have same length"
+                );
+                self.iter_mut()
+                    .zip(other.as_ref())
+                    .for_each(|(a, b)| *a = sub(*a, *b));
+                self
+            }
+
+            fn vec_conj(&mut self) -> &mut Self {
+                self.iter_mut().for_each(|a| *a = conj(*a));
+                self
+            }
+
+            fn vec_shr(&mut self, bits: u32) -> &mut Self {
+                if bits == 0 {
+                    return self;
+                }
+                let bits = bits.min(16);
+                let round = 1i32 << (bits - 1);
+                let shr = |x: i16| ((i32::from(x) + round) >> bits) as i16;
+                self.iter_mut()
+                    .for_each(|a| *a = ci16::new(shr(a.re), shr(a.im)));
+                self
+            }
+
+            fn vec_clone(&mut self, other: impl AsRef<[ci16]>) -> &mut Self {
+                assert_eq!(
+                    self.len(),
+                    other.as_ref().len(),
+                    "Vectors must have same length"
+                );
+                self.copy_from_slice(other.as_ref());
+                self
+            }
+
+            fn vec_zero(&mut self) -> &mut Self {
+                self.iter_mut().for_each(|c| *c = ci16::new(0, 0));
+                self
+            }
+        }
+    };
+}
+
+impl_fixed_vec_ops!([ci16]);
+impl_fixed_vec_ops!(Vec<ci16>);
+
+#[cfg(test)]
+mod test {
+    use crate::fixed::{self, FixedVecOps};
+    use crate::{cf32, ci16};
+
+    #[test]
+    fn q15_arithmetic() {
+        // 0.5 * 0.5 = 0.25
+        assert_eq!(fixed::q15_mul(16_384, 16_384), 8_192);
+        // -1.0 * -1.0 does not fit into Q15
+        assert_eq!(fixed::q15_mul(-32_768, -32_768), 32_767);
+        // rounding to nearest
+        assert_eq!(fixed::q15_mul(1, 16_384), 1);
+        assert_eq!(fixed::q15_mul(-1, 16_383), 0);
+
+        // j * j = -1
+        let j = ci16::new(0, 32_767);
+        assert_eq!(fixed::mul(j, j), ci16::new(-32_766, 0));
+        // (1 + j) * (1 + j) = 2j saturates
+        let c = ci16::new(32_767, 32_767);
+        assert_eq!(fixed::mul(c, c), ci16::new(0, 32_767));
+
+        assert_eq!(
+            fixed::add(ci16::new(30_000, -30_000), ci16::new(10_000, -10_000)),
+            ci16::new(32_767, -32_768)
+        );
+        assert_eq!(
+            fixed::sub(ci16::new(-30_000, 0), ci16::new(10_000, 0)),
+            ci16::new(-32_768, 0)
+        );
+        assert_eq!(fixed::conj(ci16::new(5, -32_768)), ci16::new(5, 32_767));
+    }
+
+    #[test]
+    fn conversion() {
+        let f = vec![
+            cf32::new(0.5, -0.25),
+            cf32::new(-1.0, 0.999),
+            cf32::new(2.0, -3.0),
+            cf32::new(0.0, 1.0 / 65_536.0),
+        ];
+        let mut i = vec![ci16::default(); 4];
+        fixed::from_cf32(&f, &mut i, 1.0);
+        assert_eq!(
+            i,
+            vec![
+                ci16::new(16_384, -8_192),
+                ci16::new(-32_768, 32_735),
+                // saturated
+                ci16::new(32_767, -32_768),
+                // rounded
+                ci16::new(0, 1),
+            ]
+        );
+
+        let mut back = vec![cf32::default(); 4];
+        fixed::to_cf32(&i, &mut back, 1.0);
+        assert_evm!(&back[..2], &f[..2], -40.0);
+
+        // a full scale of 2 halves the fixed-point values
+        fixed::from_cf32(&f[..1], &mut i[..1], 2.0);
+        assert_eq!(i[0], ci16::new(8_192, -4_096));
+        fixed::to_cf32(&i[..1], &mut back[..1], 2.0);
+        assert_eq!(back[0], f[0]);
+    }
+
+    #[test]
+    fn interleaved() {
+        let sc16 = [16_384i16, -32_768, 0, 8_192];
+        let mut f = vec![cf32::default(); 2];
+        fixed::sc16_to_cf32(&sc16, &mut f, 1.0);
+        assert_eq!(f, vec![cf32::new(0.5, -1.0), cf32::new(0.0, 0.25)]);
+
+        let mut out = [0i16; 4];
+        fixed::cf32_to_sc16(&f, &mut out, 1.0);
+        assert_eq!(out, sc16);
+
+        let mut c = vec![ci16::default(); 2];
+        fixed::sc16_to_ci16(&sc16, &mut c);
+        assert_eq!(c, vec![ci16::new(16_384, -32_768), ci16::new(0, 8_192)]);
+
+        let sc8 = [64i8, -128, 127, 0];
+        fixed::sc8_to_cf32(&sc8, &mut f, 4.0);
+        assert_eq!(f, vec![cf32::new(2.0, -4.0), cf32::new(3.96875, 0.0)]);
+    }
+
+    #[test]
+    fn vec_ops() {
+        let ones = vec![ci16::new(4_096, 4_096); 8];
+        let mut v = vec![ci16::new(8_192, -8_192); 8];
+
+        v.vec_add(&ones)
+            .vec_sub(&ones)
+            .vec_scale(16_384)
+            .vec_mul(&ones)
+            .vec_conj();
+        // (0.125 - 0.125j) * (0.125 + 0.125j) = 0.03125
+        assert_eq!(v[0], ci16::new(1_024, 0));
+
+        v.vec_shr(3);
+        assert_eq!(v[7], ci16::new(128, 0));
+        v[..].vec_clone(&ones).vec_shr(13);
+        assert_eq!(v[3], ci16::new(1, 1));
+
+        v.vec_zero();
+        assert!(v.iter().all(|c| *c == ci16::default()));
+    }
+}
@@ -16,6 +16,12 @@ pub type cf32 = num_complex::Complex32;
 #[allow(non_camel_case_types)]
 pub type cf64 = num_complex::Complex64;
 
+/// Shorthand for Complex<i16>
+/// Fixed-point (Q15) sample type as delivered by most SDR front-ends (sc16)
+/// This type is repr(C), thus 2 i16s back-to-back equivalent to [i16;2] on most platforms
+#[allow(non_camel_case_types)]
+pub type ci16 = num_complex::Complex<i16>;
+
 /// Error Vector Magnitude assertion
 /// Checks each element and panics if an element in the ```actual```
 /// EVM = 10 log (P_error/P_ref) => Error vector in relation to the actually expected signal in dB.
@@ -48,18 +54,47 @@ macro_rules! assert_evm {
     };
 }
 
+/// Fixed-length sample buffers aligned for SIMD instructions
+#[macro_use]
+pub mod aligned;
+
+/// Analog modulation and demodulation (AM, FM, SSB)
+pub mod analog;
+
+/// Packing and unpacking of bits to and from bytes
+pub mod bits;
+
+/// Cyclic redundancy checks (Rocksoft model) with 3GPP and other standard presets
+pub mod crc;
+
 /// Fourier Transform-related
 pub mod fft;
 
 /// FIR: Finite Impulse Response Filters
 pub mod fir;
 
+/// Fixed-point (Q15) arithmetic and conversions for ```ci16``` samples
+pub mod fixed;
+
 /// Conversion of bits into to Q/I symbols and back
 pub mod modulation;
 
+/// Numerically controlled oscillators for tone generation and frequency shifting
+pub mod nco;
+
 /// Helpers for generating AWGN noise
 pub mod noise;
 
+/// OFDM modulation and demodulation (subcarrier mapping, pilots, cyclic prefix)
+/// FEATURE: requires an fft implementation (```fft```)
+#[cfg(feature = "fft")]
+pub mod ofdm;
+
+/// Multi-threaded versions of element-wise vector operations for large buffers
+/// FEATURE: enabled via ```parallel```
+#[cfg(feature = "parallel")]
+pub mod parallel;
+
 /// Helpers to instantiate thread-based processing pipelines
 /// built atop of std::syn::mpsc channels
 pub mod pipeline;
@@ -73,12 +108,20 @@ pub mod sampling;
 /// Pseudo-Random Sequence Generation
 pub mod sequence;
 
+/// Explicitly vectorised (SSE2/AVX2) versions of common vector operations
+/// FEATURE: enabled via ```simd```
+#[cfg(feature = "simd")]
+pub mod simd;
+
 /// Miscelaneous Helpers
 pub mod util;
 
 /// Neat operations on vectors and slices
 pub mod vecops;
 
+/// Statistics (min, max, mean, power) of vectors and slices
+pub mod vecstats;
+
 #[cfg(test)]
 mod test {
     use super::cf32;
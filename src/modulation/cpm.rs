@@ -0,0 +1,300 @@
+use crate::cf32;
+use std::f32::consts::PI;
+
+/// Default length of the gaussian filter in symbols
+pub const GAUSSIAN_SPAN: usize = 4;
+
+/// Frequency pulse of GMSK/GFSK: a rectangular pulse of one symbol filtered by a gaussian
+/// with the bandwidth-time product ```bt``` (e.g. 0.3 for GSM, 0.5 for Bluetooth LE)
+/// truncated to ```span``` symbols.
+/// The pulse has ```(span + 1) * sps``` samples and is normalised to a sum of 1.
+pub fn gaussian_pulse(sps: usize, bt: f32, span: usize) -> Vec<f32> {
+    assert!(sps > 0 && bt > 0.0, "Invalid gaussian pulse parameters");
+    // standard deviation of the gaussian in samples
+    let sigma = 2f32.ln().sqrt() / (2.0 * PI * bt) * sps as f32;
+    let len = span * sps + 1;
+    let mid = (len / 2) as f32;
+    let gauss = (0..len)
+        .map(|n| (-(n as f32 - mid).powi(2) / (2.0 * sigma * sigma)).exp())
+        .collect::<Vec<_>>();
+
+    // convolve with the rectangular pulse of one symbol
+    let mut pulse = vec![0f32; len + sps - 1];
+    for (n, g) in gauss.iter().enumerate() {
+        pulse[n..n + sps].iter_mut().for_each(|p| *p += g);
+    }
+    let sum = pulse.iter().sum::<f32>();
+    pulse.iter_mut().for_each(|p| *p /= sum);
+    pulse
+}
+
+/// Rectangular frequency pulse of one symbol normalised to a sum of 1 (MSK, FSK)
+pub fn rect_pulse(sps: usize) -> Vec<f32> {
+    assert!(sps > 0, "Samples per symbol must be positive");
+    vec![1.0 / sps as f32; sps]
+}
+
+/// Instantaneous frequency (in units of the peak deviation) of the given symbol amplitudes
+/// filtered by ```pulse```, with ```sps``` samples per symbol.
+/// Symbol ```k``` contributes to samples ```k * sps..k * sps + pulse.len()```.
+pub(crate) fn frequency(amplitudes: &[f32], pulse: &[f32], sps: usize) -> Vec<f32> {
+    let len = amplitudes.len() * sps + pulse.len().saturating_sub(sps);
+    let mut f = vec![0f32; len];
+    for (k, a) in amplitudes.iter().enumerate() {
+        f[k * sps..k * sps + pulse.len()]
+            .iter_mut()
+            .zip(pulse)
+            .for_each(|(f, p)| *f += a * p * sps as f32);
+    }
+    f
+}
+
+/// Integrate the frequency to a constant-envelope signal
+/// ```phase_per_symbol``` is the phase change caused by a full symbol with amplitude 1
+pub(crate) fn integrate(f: &[f32], phase_per_symbol: f32, sps: usize) -> Vec<cf32> {
+    let step = phase_per_symbol / sps as f32;
+    let mut phase = 0f32;
+    f.iter()
+        .map(|f| {
+            phase = (phase + step * f) % (2.0 * PI);
+            cf32::from_polar(&1.0, &phase)
+        })
+        .collect()
+}
+
+/// Phase difference of each symbol interval: the argument of ```y[n1] * conj(y[n0])```
+/// where ```n0..n1``` is the symbol interval (including the pulse delay)
+pub(crate) fn phase_differences(samples: &[cf32], pulse_len: usize, sps: usize) -> Vec<f32> {
+    let tail = pulse_len.saturating_sub(sps);
+    let delay = tail / 2;
+    let symbols = samples.len().saturating_sub(tail) / sps;
+    (0..symbols)
+        .map(|k| {
+            let end = k * sps + delay + sps - 1;
+            // the signal starts with phase 0
+            let start = if k * sps + delay == 0 {
+                cf32::new(1.0, 0.0)
+            } else {
+                samples[k * sps + delay - 1]
+            };
+            (samples[end] * start.conj()).arg()
+        })
+        .collect()
+}
+
+/// Continuous-phase modulation with a modulation index of 0.5: MSK and GMSK
+/// (e.g. GSM with ```bt = 0.3```) producing oversampled ```cf32``` with ```sps``` samples per symbol.
+///
+/// Bit 0 increases the phase by ```pi/2``` over a symbol, bit 1 decreases it.
+/// The signal starts with phase 0. A block of ```n``` bits results in
+/// ```n * sps + (pulse length - sps)``` samples, i.e. ```n * sps``` samples for MSK
+/// and an additional ```GAUSSIAN_SPAN * sps``` samples for the tails of the gaussian pulses.
+///
+/// # Example
+/// ```
+/// use aether_primitives::cf32;
+/// use aether_primitives::modulation::cpm::Cpm;
+///
+/// let m = Cpm::gmsk(8, 0.3);
+/// let bits = vec![0, 1, 1, 0, 1, 0, 0, 0, 1, 1];
+/// let mut samples = m.modulate(&bits);
+///
+/// // the non-coherent demodulator does not care about the carrier phase
+/// samples.iter_mut().for_each(|s| *s *= cf32::from_polar(&1.0, &1.0));
+/// let mut demod = Vec::new();
+/// m.demod_noncoherent(&samples, &mut demod);
+/// assert_eq!(bits, demod);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cpm {
+    sps: usize,
+    pulse: Vec<f32>,
+}
+
+impl Cpm {
+    /// Minimum shift keying (rectangular frequency pulse)
+    pub fn msk(sps: usize) -> Cpm {
+        Cpm {
+            sps,
+            pulse: rect_pulse(sps),
+        }
+    }
+
+    /// Gaussian minimum shift keying with the bandwidth-time product ```bt```
+    pub fn gmsk(sps: usize, bt: f32) -> Cpm {
+        Cpm {
+            sps,
+            pulse: gaussian_pulse(sps, bt, GAUSSIAN_SPAN),
+        }
+    }
+
+    /// Modulate the bits
+    pub fn modulate(&self, bits: &[u8]) -> Vec<cf32> {
+        let amplitudes = bits
+            .iter()
+            .map(|b| if *b == 0 { 1f32 } else { -1f32 })
+            .collect::<Vec<_>>();
+        let f = frequency(&amplitudes, &self.pulse, self.sps);
+        integrate(&f, PI / 2.0, self.sps)
+    }
+
+    /// Non-coherent demodulation deciding on the sign of the phase change over each symbol.
+    /// Tolerates an arbitrary (constant) carrier phase, except for the first symbol of MSK
+    /// which has no preceding sample and is referenced to phase 0.
+    pub fn demod_noncoherent(&self, samples: &[cf32], output: &mut Vec<u8>) {
+        output.extend(
+            phase_differences(samples, self.pulse.len(), self.sps)
+                .iter()
+                .map(|d| (*d < 0.0) as u8),
+        );
+    }
+
+    /// Coherent demodulation: correlates each symbol interval with the phase trajectories of
+    /// both possible bits, given the previously decided bits and a carrier phase of 0
+    /// (i.e. after carrier recovery). Uses the energy of the whole symbol, so it performs better
+    /// in noise than [demod_noncoherent](Cpm::demod_noncoherent).
+    pub fn demod_coherent(&self, samples: &[cf32], output: &mut Vec<u8>) {
+        let sps = self.sps;
+        let tail = self.pulse.len().saturating_sub(sps);
+        let delay = tail / 2;
+        let symbols = samples.len().saturating_sub(tail) / sps;
+        // phase response: the integrated pulse
+        let mut acc = 0f32;
+        let response = self
+            .pulse
+            .iter()
+            .map(|p| {
+                acc += p;
+                acc * PI / 2.0
+            })
+            .collect::<Vec<_>>();
+        let phase = |m: isize| {
+            if m < 0 {
+                0.0
+            } else {
+                *response.get(m as usize).unwrap_or(&(PI / 2.0))
+            }
+        };
+        // symbols further in the past have fully contributed their phase change
+        let memory = self.pulse.len().div_ceil(sps) + 1;
+
+        let mut decided: Vec<f32> = Vec::with_capacity(symbols);
+        let mut settled = 0f32;
+        for k in 0..symbols {
+            if k > memory {
+                settled += decided[k - memory - 1] * PI / 2.0;
+            }
+            let first = k.saturating_sub(memory);
+            let window = k * sps + delay..k * sps + delay + sps;
+            let correlation = |a: f32| {
+                window
+                    .clone()
+                    .map(|n| {
+                        let past = decided[first..]
+                            .iter()
+                            .enumerate()
+                            .map(|(j, aj)| aj * phase(n as isize - ((first + j) * sps) as isize))
+                            .sum::<f32>();
+                        let phi = settled + past + a * phase(n as isize - (k * sps) as isize);
+                        (samples[n] * cf32::from_polar(&1.0, &-phi)).re
+                    })
+                    .sum::<f32>()
+            };
+            let a = if correlation(1.0) >= correlation(-1.0) {
+                1.0
+            } else {
+                -1.0
+            };
+            output.push((a < 0.0) as u8);
+            decided.push(a);
+        }
+    }
+
+    /// Number of samples per symbol
+    pub fn sps(&self) -> usize {
+        self.sps
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::modulation::cpm::{gaussian_pulse, Cpm};
+    use crate::noise;
+    use crate::util::random_bits;
+    use assert_approx_eq::assert_approx_eq;
+    use std::f32::consts::PI;
+
+    #[test]
+    fn pulse() {
+        let p = gaussian_pulse(8, 0.3, 4);
+        assert_eq!(p.len(), 5 * 8);
+        assert_approx_eq!(p.iter().sum::<f32>(), 1.0, 1e-5);
+        // symmetric around the centre
+        for (a, b) in p.iter().zip(p.iter().rev()) {
+            assert_approx_eq!(a, b, 1e-6);
+        }
+    }
+
+    #[test]
+    fn msk_phase() {
+        // each symbol changes the phase by exactly +-pi/2
+        let m = Cpm::msk(4);
+        let s = m.modulate(&[0, 0, 1, 0]);
+        assert_eq!(s.len(), 16);
+        let expected = [PI / 2.0, PI, PI / 2.0, PI];
+        for (k, phi) in expected.iter().enumerate() {
+            let c = s[k * 4 + 3];
+            assert_evm!(&[c], &[cf32::from_polar(&1.0, phi)], -60.0);
+        }
+        assert!(s.iter().all(|s| (s.norm() - 1.0).abs() < 1e-5));
+    }
+
+    #[test]
+    fn round_trip() {
+        let bits = random_bits(500, 815);
+        for m in &[Cpm::msk(8), Cpm::gmsk(8, 0.5), Cpm::gmsk(4, 0.3)] {
+            let samples = m.modulate(&bits);
+            assert_eq!(samples.len(), 500 * m.sps() + m.pulse.len() - m.sps());
+
+            let (mut nc, mut c) = (Vec::new(), Vec::new());
+            m.demod_noncoherent(&samples, &mut nc);
+            m.demod_coherent(&samples, &mut c);
+            assert_eq!(bits, nc);
+            assert_eq!(bits, c);
+
+            // rotated by an unknown phase
+            let rotated = samples
+                .iter()
+                .map(|s| s * cf32::from_polar(&1.0, &2.5))
+                .collect::<Vec<_>>();
+            nc.clear();
+            m.demod_noncoherent(&rotated, &mut nc);
+            // without a preceding sample the first MSK symbol is referenced to phase 0
+            assert_eq!(bits[1..], nc[1..]);
+        }
+    }
+
+    #[test]
+    fn noisy() {
+        let bits = random_bits(2000, 815);
+        let m = Cpm::gmsk(8, 0.3);
+        let mut samples = m.modulate(&bits);
+        samples
+            .iter_mut()
+            .zip(noise::new(0.09, 4711).iter())
+            .for_each(|(s, n)| *s += n);
+
+        let (mut nc, mut c) = (Vec::new(), Vec::new());
+        m.demod_noncoherent(&samples, &mut nc);
+        m.demod_coherent(&samples, &mut c);
+        let errors = |d: &[u8]| d.iter().zip(&bits).filter(|(a, b)| a != b).count();
+        assert!(
+            errors(&c) <= errors(&nc),
+            "{} > {}",
+            errors(&c),
+            errors(&nc)
+        );
+        assert!(errors(&c) < 20, "{} bit errors", errors(&c));
+    }
+}
@@ -0,0 +1,187 @@
+use super::Modulation;
+use crate::cf32;
+use std::f32::consts::FRAC_1_SQRT_2;
+
+/// Differential encoder/decoder wrapping a [Modulation](Modulation) whose symbols
+/// are used as phase increments (i.e. they should have unit magnitude).
+///
+/// The transmitted symbol is ```s[n] = s[n-1] * m.symbol(idx[n])```, the receiver decides on
+/// ```y[n] * conj(y[n-1])```, so an unknown but constant phase rotation of the channel cancels out.
+/// Both directions keep their last symbol, so a stream can be processed block by block.
+/// The initial reference symbol is ```1 + 0j```.
+///
+/// # Example
+/// ```
+/// use aether_primitives::cf32;
+/// use aether_primitives::modulation::differential;
+///
+/// let bits = vec![0, 1, 1, 1, 1, 0, 0, 0];
+/// let mut tx = differential::pi4_dqpsk();
+/// let mut symbols = tx.modulate(&bits);
+///
+/// // rotate by an arbitrary phase
+/// symbols.iter_mut().for_each(|s| *s *= cf32::from_polar(&1.0, &2.0));
+///
+/// let mut rx = differential::pi4_dqpsk();
+/// // the first symbol only sets the phase reference at the receiver
+/// let mut demod = Vec::new();
+/// rx.demod(&mut [cf32::from_polar(&1.0, &2.0)].iter(), &mut demod);
+/// rx.demod(&mut symbols.iter(), &mut demod);
+/// assert_eq!(demod[2..], bits[..]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Differential<M: Modulation> {
+    m: M,
+    /// last transmitted symbol
+    tx: cf32,
+    /// last received symbol
+    rx: cf32,
+}
+
+impl<M: Modulation> Differential<M> {
+    /// Create a differential modulator using the symbols of ```m``` as phase increments
+    pub fn new(m: M) -> Differential<M> {
+        Differential {
+            m,
+            tx: cf32::new(1.0, 0.0),
+            rx: cf32::new(1.0, 0.0),
+        }
+    }
+
+    /// Differentially modulate the bits (same conventions as [Modulation::modulate](Modulation::modulate))
+    pub fn modulate(&mut self, input: &[u8]) -> Vec<cf32> {
+        input
+            .chunks(M::BITS_PER_SYMBOL)
+            .map(|bits| {
+                self.tx *= self.m.symbol(M::index(bits));
+                self.tx
+            })
+            .collect()
+    }
+
+    /// Differentially demodulate the symbols, pushing one byte per bit to ```output```.
+    /// Each decision is based on the phase difference to the preceding symbol.
+    pub fn demod<'a>(
+        &mut self,
+        symbols: &mut impl Iterator<Item = &'a cf32>,
+        output: &mut Vec<u8>,
+    ) {
+        for s in symbols {
+            let diff = s * self.rx.conj();
+            self.rx = *s;
+            // only the phase of the difference matters
+            let norm = diff.norm();
+            let diff = if norm > 0.0 { diff / norm } else { diff };
+            self.m.demod_naive(&mut [diff].iter(), output);
+        }
+    }
+
+    /// Reset the phase reference of both the modulator and the demodulator to ```1 + 0j```
+    pub fn reset(&mut self) {
+        self.tx = cf32::new(1.0, 0.0);
+        self.rx = cf32::new(1.0, 0.0);
+    }
+
+    /// Number of bits modulated into one symbol
+    pub fn bits_per_symbol(&self) -> usize {
+        M::BITS_PER_SYMBOL
+    }
+}
+
+/// Get a differential BPSK modulator
+/// Bit 0 keeps the phase, bit 1 shifts it by pi
+pub fn dbpsk() -> Differential<[cf32; 2]> {
+    Differential::new([cf32::new(1.0, 0.0), cf32::new(-1.0, 0.0)])
+}
+
+/// Get a differential QPSK modulator
+/// Gray-coded phase shifts (```b0 b1```): 00 => 0, 01 => pi/2, 11 => pi, 10 => -pi/2
+pub fn dqpsk() -> Differential<[cf32; 4]> {
+    Differential::new([
+        cf32::new(1.0, 0.0),
+        cf32::new(0.0, -1.0),
+        cf32::new(0.0, 1.0),
+        cf32::new(-1.0, 0.0),
+    ])
+}
+
+/// Get a pi/4-DQPSK modulator as used by TETRA and Bluetooth EDR (2 Mbit/s)
+/// Gray-coded phase shifts (```b0 b1```): 00 => pi/4, 01 => 3pi/4, 11 => -3pi/4, 10 => -pi/4
+/// The transmitted symbols alternate between two QPSK constellations rotated by pi/4,
+/// so the signal never crosses the origin.
+pub fn pi4_dqpsk() -> Differential<[cf32; 4]> {
+    let h = FRAC_1_SQRT_2;
+    Differential::new([
+        cf32::new(h, h),
+        cf32::new(h, -h),
+        cf32::new(-h, h),
+        cf32::new(-h, -h),
+    ])
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::modulation::differential::{dbpsk, dqpsk, pi4_dqpsk, Differential};
+    use crate::modulation::{psk8, Modulation};
+    use crate::util::random_bits;
+    use assert_approx_eq::assert_approx_eq;
+    use std::f32::consts::PI;
+
+    /// modulates and demodulates in blocks with a rotated channel
+    fn round_trip<M: Modulation + Clone>(d: Differential<M>) {
+        let bits = random_bits(d.bits_per_symbol() * 300, 815);
+        let (mut tx, mut rx) = (d.clone(), d);
+
+        let rot = cf32::from_polar(&1.5, &-2.2);
+        let mut demod = Vec::new();
+        // the receiver needs the initial reference symbol
+        rx.demod(&mut [rot].iter(), &mut demod);
+        demod.clear();
+
+        for block in bits.chunks(tx.bits_per_symbol() * 7) {
+            let mut symbols = tx.modulate(block);
+            symbols.iter_mut().for_each(|s| *s *= rot);
+            rx.demod(&mut symbols.iter(), &mut demod);
+        }
+        assert_eq!(bits, demod);
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip(dbpsk());
+        round_trip(dqpsk());
+        round_trip(pi4_dqpsk());
+        round_trip(Differential::new(psk8(0.0)));
+    }
+
+    #[test]
+    fn phase_shifts() {
+        let mut m = dqpsk();
+        let s = m.modulate(&[0, 0, 0, 1, 1, 1, 1, 0]);
+        // shifts by 0, pi/2, pi, -pi/2
+        let expected = [
+            cf32::new(1.0, 0.0),
+            cf32::new(0.0, 1.0),
+            cf32::new(0.0, -1.0),
+            cf32::new(-1.0, 0.0),
+        ];
+        assert_evm!(&s, &expected);
+
+        m.reset();
+        assert_approx_eq!(m.modulate(&[1, 1])[0].re, -1.0);
+    }
+
+    #[test]
+    fn pi4_alternates() {
+        let bits = random_bits(400, 815);
+        let s = pi4_dqpsk().modulate(&bits);
+        for (n, s) in s.iter().enumerate() {
+            // phases are multiples of pi/4: odd multiples after odd numbers of symbols
+            let k = (s.arg() / (PI / 4.0)).round();
+            assert_approx_eq!(s.arg(), k * PI / 4.0, 1e-3);
+            assert_eq!((k as i32).rem_euclid(2), ((n + 1) % 2) as i32);
+            assert_approx_eq!(s.norm(), 1.0, 1e-4);
+        }
+    }
+}
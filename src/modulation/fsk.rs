@@ -0,0 +1,225 @@
+use super::cpm::{frequency, gaussian_pulse, integrate, rect_pulse, GAUSSIAN_SPAN};
+use crate::cf32;
+use std::f32::consts::PI;
+
+/// Frequency levels (in units of half the tone spacing) of 2-FSK indexed by the symbol index
+const LEVELS_2: [f32; 2] = [1.0, -1.0];
+/// Frequency levels of 4-FSK indexed by the symbol index (```b0 + 2 * b1```)
+/// ```b0``` selects the sign, ```b1``` the outer levels, i.e. the Gray-coded
+/// P25/DMR mapping ```01 => +3, 00 => +1, 10 => -1, 11 => -3``` (bits in order ```b0 b1```).
+const LEVELS_4: [f32; 4] = [1.0, -1.0, 3.0, -3.0];
+
+/// Continuous-phase frequency shift keying (2-FSK, 4-FSK and GFSK) producing oversampled
+/// ```cf32``` with ```sps``` samples per symbol, and a quadrature discriminator demodulator.
+///
+/// The bits are split into symbols like [Modulation::modulate](crate::modulation::Modulation::modulate),
+/// i.e. ```b0``` is the least significant bit of the symbol index.
+/// Bit 0 results in a positive frequency (see ```LEVELS_4``` for 4-FSK).
+/// The modulation index ```h``` is the spacing of adjacent tones relative to the symbol rate
+/// (e.g. 0.5 for Bluetooth LE, 0.333 for DMR), so the level ```+-1``` changes the phase by
+/// ```+-pi*h``` over a symbol.
+/// The signal starts with phase 0. A block of ```n``` symbols results in
+/// ```n * sps + (pulse length - sps)``` samples.
+///
+/// # Example
+/// ```
+/// use aether_primitives::modulation::fsk::Fsk;
+///
+/// let m = Fsk::fsk4(10, 0.333);
+/// let bits = vec![0, 1, 0, 0, 1, 0, 1, 1];
+/// let samples = m.modulate(&bits);
+/// assert_eq!(samples.len(), 4 * 10);
+///
+/// let mut soft = Vec::new();
+/// m.demod_soft(&samples, &mut soft);
+/// assert!((soft[0] - 3.0).abs() < 1e-3);
+///
+/// let mut demod = Vec::new();
+/// m.demod(&samples, &mut demod);
+/// assert_eq!(bits, demod);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fsk {
+    bits_per_symbol: usize,
+    sps: usize,
+    h: f32,
+    pulse: Vec<f32>,
+}
+
+impl Fsk {
+    /// Binary FSK with modulation index ```h``` (rectangular frequency pulse)
+    pub fn fsk2(sps: usize, h: f32) -> Fsk {
+        Fsk {
+            bits_per_symbol: 1,
+            sps,
+            h,
+            pulse: rect_pulse(sps),
+        }
+    }
+
+    /// 4-FSK with modulation index ```h``` between adjacent tones (rectangular frequency pulse)
+    pub fn fsk4(sps: usize, h: f32) -> Fsk {
+        Fsk {
+            bits_per_symbol: 2,
+            sps,
+            h,
+            pulse: rect_pulse(sps),
+        }
+    }
+
+    /// Binary gaussian FSK with modulation index ```h``` and the bandwidth-time product ```bt```
+    /// (Bluetooth LE uses ```h = 0.5```, ```bt = 0.5```)
+    pub fn gfsk(sps: usize, h: f32, bt: f32) -> Fsk {
+        Fsk {
+            bits_per_symbol: 1,
+            sps,
+            h,
+            pulse: gaussian_pulse(sps, bt, GAUSSIAN_SPAN),
+        }
+    }
+
+    fn levels(&self) -> &'static [f32] {
+        if self.bits_per_symbol == 1 {
+            &LEVELS_2
+        } else {
+            &LEVELS_4
+        }
+    }
+
+    /// Modulate the bits
+    pub fn modulate(&self, bits: &[u8]) -> Vec<cf32> {
+        let levels = self.levels();
+        let amplitudes = bits
+            .chunks(self.bits_per_symbol)
+            .map(|b| {
+                let idx = b
+                    .iter()
+                    .enumerate()
+                    .map(|(i, b)| (*b as usize) << i)
+                    .sum::<usize>();
+                levels[idx]
+            })
+            .collect::<Vec<_>>();
+        let f = frequency(&amplitudes, &self.pulse, self.sps);
+        integrate(&f, PI * self.h, self.sps)
+    }
+
+    /// Quadrature discriminator: sums the phase differences of consecutive samples over each
+    /// symbol interval, pushing one soft symbol per symbol to ```output```.
+    /// The soft symbols are scaled to the frequency levels (```+-1```, ```+-3```).
+    /// Tolerates an arbitrary (constant) carrier phase, except for the first sample
+    /// which is referenced to phase 0.
+    pub fn demod_soft(&self, samples: &[cf32], output: &mut Vec<f32>) {
+        let sps = self.sps;
+        let tail = self.pulse.len().saturating_sub(sps);
+        let delay = tail / 2;
+        let symbols = samples.len().saturating_sub(tail) / sps;
+        let scale = (PI * self.h).recip();
+
+        for k in 0..symbols {
+            let start = k * sps + delay;
+            let phase = (start..start + sps)
+                .map(|n| {
+                    let prev = if n == 0 {
+                        cf32::new(1.0, 0.0)
+                    } else {
+                        samples[n - 1]
+                    };
+                    (samples[n] * prev.conj()).arg()
+                })
+                .sum::<f32>();
+            output.push(phase * scale);
+        }
+    }
+
+    /// Hard demodulate by deciding on the nearest frequency level of each soft symbol,
+    /// pushing one byte per bit to ```output```
+    pub fn demod(&self, samples: &[cf32], output: &mut Vec<u8>) {
+        let mut soft = Vec::with_capacity(samples.len() / self.sps);
+        self.demod_soft(samples, &mut soft);
+        for s in soft {
+            output.push((s < 0.0) as u8);
+            if self.bits_per_symbol == 2 {
+                output.push((s.abs() > 2.0) as u8);
+            }
+        }
+    }
+
+    /// Number of bits modulated into one symbol
+    pub fn bits_per_symbol(&self) -> usize {
+        self.bits_per_symbol
+    }
+
+    /// Number of samples per symbol
+    pub fn sps(&self) -> usize {
+        self.sps
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::modulation::fsk::Fsk;
+    use crate::noise;
+    use crate::util::random_bits;
+    use assert_approx_eq::assert_approx_eq;
+    use std::f32::consts::PI;
+
+    #[test]
+    fn levels() {
+        // tones at +-h/2 and +-3h/2 times the symbol rate
+        let m = Fsk::fsk4(8, 0.25);
+        let s = m.modulate(&[0, 1, 0, 0, 1, 0, 1, 1]);
+        assert_eq!(s.len(), 32);
+        let expected = [3.0, 1.0, -1.0, -3.0];
+        for (k, level) in expected.iter().enumerate() {
+            let step = s[k * 8 + 4] * s[k * 8 + 3].conj();
+            assert_approx_eq!(step.arg(), level * PI * 0.25 / 8.0, 1e-5);
+        }
+        assert!(s.iter().all(|s| (s.norm() - 1.0).abs() < 1e-5));
+    }
+
+    #[test]
+    fn round_trip() {
+        let bits = random_bits(1000, 815);
+        let modems = [
+            Fsk::fsk2(8, 1.0),
+            Fsk::fsk2(4, 0.5),
+            Fsk::fsk4(10, 0.333),
+            Fsk::gfsk(8, 0.5, 0.5),
+        ];
+        for m in modems.iter() {
+            let samples = m.modulate(&bits);
+            let symbols = bits.len() / m.bits_per_symbol();
+            let mut soft = Vec::new();
+            m.demod_soft(&samples, &mut soft);
+            assert_eq!(soft.len(), symbols);
+
+            // rotated by an unknown phase
+            let rotated = samples
+                .iter()
+                .map(|s| s * cf32::from_polar(&1.0, &-1.3))
+                .collect::<Vec<_>>();
+            let mut demod = Vec::new();
+            m.demod(&rotated, &mut demod);
+            let b = m.bits_per_symbol();
+            assert_eq!(bits[b..], demod[b..]);
+        }
+    }
+
+    #[test]
+    fn noisy() {
+        let bits = random_bits(2000, 815);
+        let m = Fsk::fsk2(8, 1.0);
+        let mut samples = m.modulate(&bits);
+        samples
+            .iter_mut()
+            .zip(noise::new(0.05, 4711).iter())
+            .for_each(|(s, n)| *s += n);
+
+        let mut demod = Vec::new();
+        m.demod(&samples, &mut demod);
+        let errors = demod.iter().zip(&bits).filter(|(a, b)| a != b).count();
+        assert!(errors < 10, "{} bit errors", errors);
+    }
+}
@@ -0,0 +1,364 @@
+use crate::bits::{BitOrder, BitSlice};
+use crate::cf32;
+use std::cmp::{Ordering, PartialOrd};
+
+/// Continuous-phase modulation (MSK, GMSK)
+pub mod cpm;
+/// Differential modulation (DBPSK, DQPSK, pi/4-DQPSK)
+pub mod differential;
+/// Frequency shift keying (2-FSK, 4-FSK, GFSK)
+pub mod fsk;
+/// Offset-QPSK with rectangular or half-sine pulses
+pub mod oqpsk;
+mod psk;
+mod qam;
+mod slicer;
+pub use self::psk::{apsk16, apsk32, psk16, psk8};
+pub use self::qam::{qam1024, qam16, qam256, qam64, QamDemapper, QamMapping};
+pub use self::slicer::Slicer;
+
+/// Blanket impl for cf32;2 array
+impl Modulation for [cf32; 2] {
+    const BITS_PER_SYMBOL: usize = 1;
+
+    fn index(bits: &[u8]) -> usize {
+        debug_assert!(bits.len() == Self::BITS_PER_SYMBOL);
+        bits[0] as usize
+    }
+
+    fn symbol(&self, idx: usize) -> cf32 {
+        self[idx]
+    }
+}
+
+/// Blanket impl for cf32;4 array
+impl Modulation for [cf32; 4] {
+    const BITS_PER_SYMBOL: usize = 2;
+
+    fn index(bits: &[u8]) -> usize {
+        debug_assert!(bits.len() == Self::BITS_PER_SYMBOL);
+        ((bits[1] << 1) + bits[0]) as usize
+    }
+
+    fn symbol(&self, idx: usize) -> cf32 {
+        self[idx]
+    }
+
+    // this slightly optimized version cuts demod time by roughly 20%
+    // as opposed to the non-vectorized version
+    fn demod_naive<'a>(&self, symbols: &mut impl Iterator<Item = &'a cf32>, output: &mut Vec<u8>) {
+        let dist = |s: &cf32, idx: usize| {
+            // lets hope this gets vectorized
+            (
+                idx,
+                (s.re - self[idx].re) * (s.re - self[idx].re)
+                    + (s.im - self[idx].im) * (s.im - self[idx].im),
+            )
+        };
+
+        let min_idx = |s| {
+            [dist(s, 0), dist(s, 1), dist(s, 2), dist(s, 3)]
+                .into_iter()
+                .min_by(|d, e| d.1.partial_cmp(&e.1).unwrap_or(Ordering::Greater))
+                .map(|(idx, _)| *idx)
+                .unwrap()
+        };
+
+        for s in symbols {
+            let idx = min_idx(s) as u8;
+            output.push(idx & 1u8);
+            output.push(idx >> 1 & 1u8);
+        }
+    }
+}
+
+/// Modulation for lookup tables with 2^BITS_PER_SYMBOL entries
+macro_rules! impl_modulation {
+    ($len:expr, $bits:expr) => {
+        /// Blanket impl for larger lookup tables (e.g. QAM, PSK, APSK)
+        impl Modulation for [cf32; $len] {
+            const BITS_PER_SYMBOL: usize = $bits;
+
+            fn symbol(&self, idx: usize) -> cf32 {
+                self[idx]
+            }
+        }
+    };
+}
+
+impl_modulation!(8, 3);
+impl_modulation!(16, 4);
+impl_modulation!(32, 5);
+impl_modulation!(64, 6);
+impl_modulation!(256, 8);
+impl_modulation!(1024, 10);
+
+/// LLR computation shared by the soft demodulators
+/// ```combine``` reduces the metrics ```-|y-s|^2 / (2 noise_var)``` of all symbols with a bit set
+/// to a given value
+fn llr<'a, M>(
+    m: &M,
+    symbols: &mut impl Iterator<Item = &'a cf32>,
+    noise_var: f32,
+    output: &mut Vec<f32>,
+    combine: impl Fn(&[f32]) -> f32,
+) where
+    M: Modulation + ?Sized,
+{
+    let len = 1usize << M::BITS_PER_SYMBOL;
+    let mut metrics = vec![0f32; len];
+    let (mut zeros, mut ones) = (Vec::with_capacity(len / 2), Vec::with_capacity(len / 2));
+    let scale = -0.5 / noise_var;
+    for symbol in symbols {
+        metrics
+            .iter_mut()
+            .enumerate()
+            .for_each(|(idx, mt)| *mt = (*symbol - m.symbol(idx)).norm_sqr() * scale);
+
+        for i in 0..M::BITS_PER_SYMBOL {
+            zeros.clear();
+            ones.clear();
+            for (idx, mt) in metrics.iter().enumerate() {
+                if idx >> i & 1 == 0 {
+                    zeros.push(*mt);
+                } else {
+                    ones.push(*mt);
+                }
+            }
+            output.push(combine(&zeros) - combine(&ones));
+        }
+    }
+}
+
+/// ```ln(sum(exp(x)))``` computed without overflowing
+fn log_sum_exp(metrics: &[f32]) -> f32 {
+    let max = max(metrics);
+    if max == f32::NEG_INFINITY {
+        return max;
+    }
+    max + metrics.iter().map(|m| (m - max).exp()).sum::<f32>().ln()
+}
+
+fn max(metrics: &[f32]) -> f32 {
+    metrics.iter().cloned().fold(f32::NEG_INFINITY, f32::max)
+}
+
+/// Get a generic BPSK modulator
+/// Ensure the [modulation::Modulation] trait is in scope
+pub fn bpsk() -> [cf32; 2] {
+    GENERIC_BPSK_TABLE
+}
+
+/// Get a generic QPSK modulator
+pub fn qpsk() -> [cf32; 4] {
+    GENERIC_QPSK_TABLE
+}
+
+/// A Generic Binary Phase-Shift Keying Modulation
+/// # Constellation Diagram:
+/// ```text
+///          | 0                     | 0
+/// bits   ----- --> table index:  -----
+///        1 |                     1 |
+/// ```
+pub const GENERIC_BPSK_TABLE: [cf32; 2] = [cf32 { re: 1.0, im: 1.0 }, cf32 { re: -1.0, im: -1.0 }];
+
+/// A Generic Quadrature Phase-Shift Keying Modulation
+/// Eqivalent to a 4-Quadrature Amplitude Modulation  
+/// # Constellation Diagram:
+/// ```text
+///          01|00                   1 | 0
+/// bits     ----- --> table index:  -----
+///          11|10                   3 | 2
+/// ```
+pub const GENERIC_QPSK_TABLE: [cf32; 4] = [
+    cf32 { re: 1.0, im: 1.0 },
+    cf32 { re: -1.0, im: 1.0 },
+    cf32 { re: 1.0, im: -1.0 },
+    cf32 { re: -1.0, im: -1.0 },
+];
+
+pub trait Modulation {
+    /// Number of bits modulated into one symbol
+    const BITS_PER_SYMBOL: usize;
+
+    /// Get the actual symbol value
+    fn symbol(&self, idx: usize) -> cf32;
+
+    /// Blanket impl
+    /// Treats each bit as a single byte
+    /// Expects each input byte in the range [0..=1]
+    /// This is the blanket (slow) impl
+    #[inline(always)]
+    fn index(bits: &[u8]) -> usize {
+        debug_assert!(bits.len() == Self::BITS_PER_SYMBOL);
+        bits.iter()
+            .enumerate()
+            .map(|(i, bit)| (*bit as usize % 2) << i)
+            .sum()
+    }
+
+    #[inline(always)]
+    fn modulate(&self, input: &[u8]) -> Vec<cf32> {
+        input
+            .chunks(Self::BITS_PER_SYMBOL)
+            .map(Self::index)
+            .map(|idx| self.symbol(idx))
+            .collect()
+    }
+
+    #[inline(always)]
+    fn modulate_into<'a>(&self, input: &[u8], output: &mut impl Iterator<Item = &'a mut cf32>) {
+        input
+            .chunks(Self::BITS_PER_SYMBOL)
+            .map(Self::index)
+            .map(|idx| self.symbol(idx))
+            .zip(output)
+            .for_each(|(s, out)| *out = s);
+    }
+
+    /// Modulate packed bytes without unpacking them into one bit per byte first.
+    /// The bits are taken in the given order and split into symbols like
+    /// [modulate](Modulation::modulate) does; trailing bits which do not fill a symbol are ignored.
+    /// # Example
+    /// ```
+    /// use aether_primitives::bits::{self, BitOrder};
+    /// use aether_primitives::modulation::{qpsk, Modulation};
+    ///
+    /// let bytes = [0x1B, 0xE4];
+    /// let m = qpsk();
+    /// let unpacked = bits::unpack(&bytes, BitOrder::MsbFirst);
+    /// assert_eq!(m.modulate_packed(&bytes, BitOrder::MsbFirst), m.modulate(&unpacked));
+    /// ```
+    fn modulate_packed(&self, bytes: &[u8], order: BitOrder) -> Vec<cf32> {
+        let bits = BitSlice::new(bytes, order);
+        let mut chunk = [0u8; 64];
+        let chunk = &mut chunk[..Self::BITS_PER_SYMBOL];
+        (0..bits.len() / Self::BITS_PER_SYMBOL)
+            .map(|s| {
+                chunk
+                    .iter_mut()
+                    .enumerate()
+                    .for_each(|(i, b)| *b = bits.get(s * Self::BITS_PER_SYMBOL + i));
+                self.symbol(Self::index(chunk))
+            })
+            .collect()
+    }
+
+    fn demod_naive<'a>(&self, symbols: &mut impl Iterator<Item = &'a cf32>, output: &mut Vec<u8>) {
+        for symbol in symbols {
+            let (idx, _symb) = (0..1 << Self::BITS_PER_SYMBOL)
+                .map(|idx| *symbol - self.symbol(idx))
+                .map(|dist| dist.re * dist.re + dist.im * dist.im)
+                .enumerate()
+                .min_by(|(_i, d), (_j, e)| d.partial_cmp(e).unwrap_or(Ordering::Greater))
+                .expect("Finding the minimum symbol distance failed");
+
+            output.extend((0..Self::BITS_PER_SYMBOL).map(|i| (idx >> i & 1) as u8))
+        }
+    }
+
+    /// Soft demodulation using the exact log-MAP rule.
+    /// Pushes one log-likelihood ratio ```ln(P(b=0|y) / P(b=1|y))``` per bit to ```output```
+    /// (positive values favour 0, in the same bit order as [modulate](Modulation::modulate)).  
+    /// ```noise_var``` is the noise variance per real dimension,
+    /// i.e. the ```power``` of a [noise::Awgn](crate::noise::Awgn).
+    /// Equally likely symbols are assumed.
+    fn demod_llr<'a>(
+        &self,
+        symbols: &mut impl Iterator<Item = &'a cf32>,
+        noise_var: f32,
+        output: &mut Vec<f32>,
+    ) {
+        llr(self, symbols, noise_var, output, log_sum_exp)
+    }
+
+    /// Soft demodulation using the max-log approximation of [demod_llr](Modulation::demod_llr),
+    /// i.e. only the closest symbol with the bit set to 0 resp. 1 is taken into account
+    fn demod_llr_maxlog<'a>(
+        &self,
+        symbols: &mut impl Iterator<Item = &'a cf32>,
+        noise_var: f32,
+        output: &mut Vec<f32>,
+    ) {
+        llr(self, symbols, noise_var, output, max)
+    }
+
+    fn bits_per_symbol(&self) -> usize {
+        Self::BITS_PER_SYMBOL
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bits::{self, BitOrder};
+    use crate::cf32;
+    use crate::modulation::{bpsk, psk8, qam64, qpsk, Modulation, QamMapping, GENERIC_QPSK_TABLE};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn generic_bpsk() {
+        let input: Vec<u8> = [0, 1, 0, 1].iter().map(|i| *i as u8).collect();
+
+        let output = bpsk().modulate(&input);
+
+        assert_eq!(
+            output,
+            vec![
+                cf32 { re: 1.0, im: 1.0 },
+                cf32 { re: -1.0, im: -1.0 },
+                cf32 { re: 1.0, im: 1.0 },
+                cf32 { re: -1.0, im: -1.0 },
+            ]
+        )
+    }
+
+    #[test]
+    fn generic_qpsk() {
+        let input: Vec<u8> = [0, 0, 1, 0, 0, 1, 1, 1].iter().map(|i| *i as u8).collect();
+
+        let output = qpsk().modulate(&input);
+
+        assert_eq!(output.as_slice(), &GENERIC_QPSK_TABLE);
+    }
+
+    #[test]
+    fn naive_demod() {
+        let m = qpsk();
+
+        // generate some ones and zeroes
+        for seed in &[815u64, 234354654543, 18324357] {
+            let mut r = StdRng::seed_from_u64(*seed);
+            let bits = (0..100).map(|_| r.gen_range(0u8, 2u8)).collect::<Vec<_>>();
+            let output = m.modulate(&bits);
+            let mut demod_bits = Vec::with_capacity(100);
+            m.demod_naive(&mut output.iter(), &mut demod_bits);
+            assert_eq!(bits, demod_bits);
+        }
+    }
+
+    #[test]
+    fn packed() {
+        let mut r = StdRng::seed_from_u64(815);
+        let bytes = (0..99).map(|_| r.gen::<u8>()).collect::<Vec<_>>();
+        for order in &[BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            let unpacked = bits::unpack(&bytes, *order);
+            assert_eq!(
+                qpsk().modulate_packed(&bytes, *order),
+                qpsk().modulate(&unpacked)
+            );
+            // 792 bits fill whole 64-QAM and 8-PSK symbols
+            let m = qam64(QamMapping::Gray);
+            assert_eq!(m.modulate_packed(&bytes, *order), m.modulate(&unpacked));
+            let m = psk8(0.0);
+            let symbols = m.modulate_packed(&bytes, *order);
+            assert_eq!(symbols.len(), 264);
+            assert_eq!(symbols, m.modulate(&unpacked));
+        }
+        // a trailing incomplete symbol is dropped
+        assert_eq!(
+            psk8(0.0).modulate_packed(&[0xFF], BitOrder::MsbFirst).len(),
+            2
+        );
+    }
+}
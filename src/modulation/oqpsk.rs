@@ -0,0 +1,153 @@
+use crate::cf32;
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+/// Pulse shape of the in-phase and quadrature components of [Oqpsk](Oqpsk)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pulse {
+    /// Rectangular pulses with an amplitude of ```1/sqrt(2)``` (unit power).
+    /// The envelope is constant here; after band-limiting it only dips by about 3dB during
+    /// transitions since I and Q never switch at once (instead of passing through zero as with QPSK).
+    Rect,
+    /// Half-sine pulses with an amplitude of 1 as used by IEEE 802.15.4 (2.4 GHz).
+    /// With the half-symbol offset this results in a constant envelope (equivalent to MSK).
+    HalfSine,
+}
+
+/// Offset-QPSK modulator and integrate-and-dump demodulator producing/consuming
+/// oversampled ```cf32``` with ```sps``` samples per symbol.
+///
+/// Of each pair of bits the first one is sent on the in-phase, the second one on the quadrature
+/// component (bit 0 => positive amplitude, like [qpsk](crate::modulation::qpsk)).
+/// The quadrature component is delayed by half a symbol, so a block of ```n``` symbols
+/// results in ```n * sps + sps / 2``` samples.
+///
+/// # Example
+/// ```
+/// use aether_primitives::modulation::oqpsk::{Oqpsk, Pulse};
+///
+/// let m = Oqpsk::new(8, Pulse::HalfSine);
+/// let bits = vec![0, 1, 1, 1, 0, 0, 1, 0];
+/// let samples = m.modulate(&bits);
+/// assert_eq!(samples.len(), 4 * 8 + 4);
+///
+/// let mut demod = Vec::new();
+/// m.demod(&samples, &mut demod);
+/// assert_eq!(bits, demod);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Oqpsk {
+    sps: usize,
+    pulse: Vec<f32>,
+}
+
+impl Oqpsk {
+    /// Create an OQPSK modem with ```sps``` samples per symbol and the given pulse shape
+    /// # Panics
+    /// Panics if ```sps``` is not even (and at least 2)
+    pub fn new(sps: usize, pulse: Pulse) -> Oqpsk {
+        assert!(
+            sps >= 2 && sps % 2 == 0,
+            "Samples per symbol must be even to allow a half-symbol offset"
+        );
+        let pulse = (0..sps)
+            .map(|n| match pulse {
+                Pulse::Rect => FRAC_1_SQRT_2,
+                Pulse::HalfSine => (PI * (n as f32 + 0.5) / sps as f32).sin(),
+            })
+            .collect();
+        Oqpsk { sps, pulse }
+    }
+
+    /// Modulate the bits (a trailing odd bit is ignored)
+    pub fn modulate(&self, bits: &[u8]) -> Vec<cf32> {
+        let (sps, half) = (self.sps, self.sps / 2);
+        let symbols = bits.len() / 2;
+        let amp = |b: u8| if b == 0 { 1f32 } else { -1f32 };
+
+        let mut out = vec![cf32::default(); symbols * sps + half];
+        for (k, pair) in bits.chunks_exact(2).enumerate() {
+            let (i, q) = (amp(pair[0]), amp(pair[1]));
+            for (n, p) in self.pulse.iter().enumerate() {
+                out[k * sps + n].re = i * p;
+                out[k * sps + half + n].im = q * p;
+            }
+        }
+        out
+    }
+
+    /// Hard demodulate by correlating each component with the pulse over its symbol interval.
+    /// Expects the sample timing of [modulate](Oqpsk::modulate), i.e. the first in-phase symbol
+    /// starts at the first sample.
+    pub fn demod(&self, samples: &[cf32], output: &mut Vec<u8>) {
+        let (sps, half) = (self.sps, self.sps / 2);
+        let symbols = samples.len().saturating_sub(half) / sps;
+        let correlate = |samples: &[cf32], part: fn(&cf32) -> f32| {
+            samples
+                .iter()
+                .zip(&self.pulse)
+                .map(|(s, p)| part(s) * p)
+                .sum::<f32>()
+        };
+
+        for k in 0..symbols {
+            let i = correlate(&samples[k * sps..], |s| s.re);
+            let q = correlate(&samples[k * sps + half..], |s| s.im);
+            output.push((i < 0.0) as u8);
+            output.push((q < 0.0) as u8);
+        }
+    }
+
+    /// Number of samples per symbol
+    pub fn sps(&self) -> usize {
+        self.sps
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::modulation::oqpsk::{Oqpsk, Pulse};
+    use crate::noise;
+    use crate::util::random_bits;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn round_trip() {
+        let bits = random_bits(1000, 815);
+        for pulse in &[Pulse::Rect, Pulse::HalfSine] {
+            let m = Oqpsk::new(4, *pulse);
+            let mut samples = m.modulate(&bits);
+            assert_eq!(samples.len(), 500 * 4 + 2);
+
+            samples
+                .iter_mut()
+                .zip(noise::new(0.04, 4711).iter())
+                .for_each(|(s, n)| *s += n);
+            let mut demod = Vec::new();
+            m.demod(&samples, &mut demod);
+            assert_eq!(bits, demod);
+        }
+    }
+
+    #[test]
+    fn envelope() {
+        let bits = random_bits(200, 815);
+
+        // half sine pulses result in a constant envelope (except for the first and last half symbol)
+        let m = Oqpsk::new(16, Pulse::HalfSine);
+        let samples = m.modulate(&bits);
+        for s in &samples[8..samples.len() - 8] {
+            assert_approx_eq!(s.norm(), 1.0, 1e-5);
+        }
+
+        // the rectangular pulses never cross the origin
+        let m = Oqpsk::new(16, Pulse::Rect);
+        let samples = m.modulate(&bits);
+        for s in &samples[8..samples.len() - 8] {
+            assert_approx_eq!(s.norm(), 1.0, 1e-5);
+        }
+        for w in samples[8..samples.len() - 8].windows(2) {
+            // at most one component changes its sign at once
+            assert!((w[1] * w[0].conj()).arg().abs() <= std::f32::consts::FRAC_PI_2 + 1e-5);
+        }
+    }
+}
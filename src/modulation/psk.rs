@@ -0,0 +1,176 @@
+use crate::cf32;
+use std::f32::consts::PI;
+
+/// Get a Gray-coded 8-PSK modulator (unit power)
+/// Point ```p``` lies at ```offset + 2*pi*p/8``` (radians) and carries the bits of ```gray(p)```,
+/// with ```b0``` as the least significant bit of the label.
+/// # Example
+/// ```
+/// use aether_primitives::cf32;
+/// use aether_primitives::modulation::{psk8, Modulation};
+///
+/// let m = psk8(std::f32::consts::PI / 8.0);
+/// let s = m.modulate(&[0, 0, 0, 1, 0, 0]);
+/// assert_eq!(s[0], cf32::from_polar(&1.0, &(std::f32::consts::PI / 8.0)));
+/// // b0 b1 b2 = 1 0 0 is the label gray(1) = 0b001 of the next point
+/// assert!((s[1] - cf32::from_polar(&1.0, &(3.0 * std::f32::consts::PI / 8.0))).norm() < 1e-6);
+/// ```
+pub fn psk8(offset: f32) -> [cf32; 8] {
+    let mut table = [cf32::default(); 8];
+    fill_psk(&mut table, 1.0, offset);
+    table
+}
+
+/// Get a Gray-coded 16-PSK modulator (unit power)
+/// See [psk8](psk8) for the mapping.
+pub fn psk16(offset: f32) -> [cf32; 16] {
+    let mut table = [cf32::default(); 16];
+    fill_psk(&mut table, 1.0, offset);
+    table
+}
+
+/// Labels (```b0 b1 b2 b3```) of the inner 4-point ring of 16-APSK, counter clockwise
+const APSK16_INNER: [&str; 4] = ["1100", "1110", "1111", "1101"];
+/// Labels (```b0 b1 b2 b3```) of the outer 12-point ring of 16-APSK, counter clockwise
+/// Neighbours differ in a single bit.
+const APSK16_OUTER: [&str; 12] = [
+    "0000", "0100", "0101", "0111", "0110", "0010", "0011", "1011", "1010", "1000", "1001", "0001",
+];
+
+/// Get a 4+12 16-APSK modulator in the style of DVB-S2 (unit average power)
+/// ```ring_ratio``` is the ratio of the outer to the inner ring radius (DVB-S2 uses 2.57 to 3.15
+/// depending on the code rate), ```offset``` rotates the whole constellation (radians).
+/// The inner ring starts at ```pi/4```, the outer ring at ```pi/12```.
+/// The bit labels are Gray within each ring, but are not guaranteed to match DVB-S2 bit-for-bit.
+pub fn apsk16(ring_ratio: f32, offset: f32) -> [cf32; 16] {
+    let mut table = [cf32::default(); 16];
+    fill_apsk16(&mut table, 1.0, ring_ratio, offset);
+    normalise(&mut table);
+    table
+}
+
+/// Get a 4+12+16 32-APSK modulator in the style of DVB-S2 (unit average power)
+/// ```r1``` and ```r2``` are the ratios of the middle and the outer ring radius to the inner one
+/// (e.g. 2.84 and 5.27), ```offset``` rotates the whole constellation (radians).
+/// Symbols with ```b4 = 0``` form a [16-APSK](apsk16) on the inner rings,
+/// symbols with ```b4 = 1``` a Gray-coded [16-PSK](psk16) on the outer ring.
+/// The bit labels are not guaranteed to match DVB-S2 bit-for-bit.
+pub fn apsk32(r1: f32, r2: f32, offset: f32) -> [cf32; 32] {
+    let mut table = [cf32::default(); 32];
+    {
+        let (inner, outer) = table.split_at_mut(16);
+        fill_apsk16(inner, 1.0, r1, offset);
+        fill_psk(outer, r2, offset);
+    }
+    normalise(&mut table);
+    table
+}
+
+/// Gray-coded PSK with the given radius
+fn fill_psk(table: &mut [cf32], radius: f32, offset: f32) {
+    let m = table.len();
+    for p in 0..m {
+        let phi = offset + 2.0 * PI * p as f32 / m as f32;
+        table[p ^ (p >> 1)] = cf32::from_polar(&radius, &phi);
+    }
+}
+
+fn fill_apsk16(table: &mut [cf32], inner_radius: f32, ring_ratio: f32, offset: f32) {
+    let ring = |labels: &[&str], radius: f32, start: f32, table: &mut [cf32]| {
+        for (k, label) in labels.iter().enumerate() {
+            let phi = offset + start + 2.0 * PI * k as f32 / labels.len() as f32;
+            table[label_index(label)] = cf32::from_polar(&radius, &phi);
+        }
+    };
+    ring(&APSK16_INNER, inner_radius, PI / 4.0, table);
+    ring(&APSK16_OUTER, inner_radius * ring_ratio, PI / 12.0, table);
+}
+
+/// table index of a label written as ```b0 b1 b2 ...```
+fn label_index(label: &str) -> usize {
+    label
+        .bytes()
+        .enumerate()
+        .map(|(i, b)| ((b - b'0') as usize) << i)
+        .sum()
+}
+
+/// scale the table to unit average power
+fn normalise(table: &mut [cf32]) {
+    let power = table.iter().map(|s| s.norm_sqr()).sum::<f32>() / table.len() as f32;
+    let scale = power.sqrt().recip();
+    table.iter_mut().for_each(|s| *s = s.scale(scale));
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::modulation::{apsk16, apsk32, psk16, psk8, Modulation};
+    use assert_approx_eq::assert_approx_eq;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn power(table: &[cf32]) -> f32 {
+        table.iter().map(|s| s.norm_sqr()).sum::<f32>() / table.len() as f32
+    }
+
+    fn round_trip<M: Modulation>(m: &M) {
+        let mut r = StdRng::seed_from_u64(815);
+        let bits = (0..M::BITS_PER_SYMBOL * 200)
+            .map(|_| r.gen_range(0u8, 2u8))
+            .collect::<Vec<_>>();
+        let symbols = m.modulate(&bits);
+        let mut demod = Vec::with_capacity(bits.len());
+        m.demod_naive(&mut symbols.iter(), &mut demod);
+        assert_eq!(bits, demod);
+    }
+
+    #[test]
+    fn psk() {
+        let m = psk8(0.0);
+        assert_approx_eq!(power(&m), 1.0, 1e-5);
+        // adjacent points differ in one bit
+        let labels = (0..8)
+            .map(|p| {
+                let phi = 2.0 * std::f32::consts::PI * p as f32 / 8.0;
+                m.iter()
+                    .position(|s| (s - cf32::from_polar(&1.0, &phi)).norm() < 1e-5)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        for p in 0..8 {
+            assert_eq!((labels[p] ^ labels[(p + 1) % 8]).count_ones(), 1);
+        }
+        round_trip(&m);
+
+        let m = psk16(0.3);
+        assert_approx_eq!(power(&m), 1.0, 1e-5);
+        assert_approx_eq!(m[0].arg(), 0.3);
+        round_trip(&m);
+    }
+
+    #[test]
+    fn apsk() {
+        let m = apsk16(2.57, 0.0);
+        assert_approx_eq!(power(&m), 1.0, 1e-5);
+        let inner = m[0b0011].norm();
+        let outer = m[0].norm();
+        assert_approx_eq!(outer / inner, 2.57, 1e-5);
+        assert_eq!(
+            m.iter().filter(|s| (s.norm() - inner).abs() < 1e-5).count(),
+            4
+        );
+        round_trip(&m);
+
+        let m = apsk32(2.84, 5.27, 0.1);
+        assert_approx_eq!(power(&m), 1.0, 1e-5);
+        let inner = m[0b0011].norm();
+        assert_approx_eq!(m[0].norm() / inner, 2.84, 1e-5);
+        assert_approx_eq!(m[0b10000].norm() / inner, 5.27, 1e-5);
+        // the outer ring is a 16-PSK
+        let psk = psk16(0.1);
+        for idx in 0..16 {
+            assert_approx_eq!(m[16 + idx].arg(), psk[idx].arg(), 1e-5);
+        }
+        round_trip(&m);
+    }
+}
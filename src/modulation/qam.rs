@@ -0,0 +1,392 @@
+use crate::cf32;
+
+/// Bit-to-symbol mapping of square QAM constellations.
+/// Both mappings are Gray codes: neighbouring symbols differ in one bit only.
+/// Bit ```b0``` is the first bit of each symbol (i.e. the least significant bit of the table index).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QamMapping {
+    /// The first half of the bits selects the in-phase, the second half the quadrature amplitude.
+    /// Each half is a Gray code with its first bit as MSB; all-zero bits map to the top right corner.
+    /// For 4-QAM this equals [qpsk](crate::modulation::qpsk) (up to normalisation).
+    Gray,
+    /// LTE/NR mapping as specified in 3GPP TS 36.211 7.1 and TS 38.211 5.1:
+    /// even bits (```b0, b2, ...```) select the in-phase, odd bits (```b1, b3, ...```)
+    /// the quadrature amplitude.
+    ThreeGpp,
+}
+
+/// Get a 16-QAM modulator (unit average power)
+/// # Example
+/// ```
+/// use aether_primitives::cf32;
+/// use aether_primitives::modulation::{qam16, Modulation, QamMapping};
+///
+/// // b0..b3 = 0,0,0,0 => (1 + j) / sqrt(10) according to 3GPP TS 38.211 5.1.3
+/// let m = qam16(QamMapping::ThreeGpp);
+/// let s = m.modulate(&[0, 0, 0, 0, 1, 0, 1, 1]);
+/// assert_eq!(s[0], cf32::new(1.0, 1.0) / 10f32.sqrt());
+/// assert_eq!(s[1], cf32::new(-3.0, 3.0) / 10f32.sqrt());
+/// ```
+pub fn qam16(mapping: QamMapping) -> [cf32; 16] {
+    let mut table = [cf32::default(); 16];
+    fill_square(&mut table, mapping);
+    table
+}
+
+/// Get a 64-QAM modulator (unit average power)
+pub fn qam64(mapping: QamMapping) -> [cf32; 64] {
+    let mut table = [cf32::default(); 64];
+    fill_square(&mut table, mapping);
+    table
+}
+
+/// Get a 256-QAM modulator (unit average power)
+pub fn qam256(mapping: QamMapping) -> [cf32; 256] {
+    let mut table = [cf32::default(); 256];
+    fill_square(&mut table, mapping);
+    table
+}
+
+/// Get a 1024-QAM modulator (unit average power)
+pub fn qam1024(mapping: QamMapping) -> [cf32; 1024] {
+    let mut table = [cf32::default(); 1024];
+    fill_square(&mut table, mapping);
+    table
+}
+
+/// Number of bits per axis of a square QAM table
+pub(crate) fn axis_bits(table_len: usize) -> usize {
+    let bits = table_len.trailing_zeros() as usize;
+    assert!(
+        table_len.is_power_of_two() && bits % 2 == 0,
+        "Square QAM requires an even number of bits per symbol"
+    );
+    bits / 2
+}
+
+/// Position of the ```i```-th bit (in the order of the specification) of an axis within the table index
+pub(crate) fn bit_pos(i: usize, bits: usize, mapping: QamMapping, quadrature: bool) -> usize {
+    match mapping {
+        QamMapping::Gray => i + if quadrature { bits } else { 0 },
+        QamMapping::ThreeGpp => 2 * i + if quadrature { 1 } else { 0 },
+    }
+}
+
+/// The bits of the given axis of symbol ```idx```, in the order of the specification
+/// (i.e. the first one is the sign bit)
+fn axis(
+    idx: usize,
+    bits: usize,
+    mapping: QamMapping,
+    quadrature: bool,
+) -> impl Iterator<Item = u8> {
+    (0..bits).map(move |i| (idx >> bit_pos(i, bits, mapping, quadrature) & 1) as u8)
+}
+
+/// Amplitude level of an axis in -(L-1)..=L-1 (odd integers) for ```L = 2^bits``` levels
+pub(crate) fn level(idx: usize, bits: usize, mapping: QamMapping, quadrature: bool) -> i32 {
+    let axis_bits = axis(idx, bits, mapping, quadrature).collect::<Vec<_>>();
+    match mapping {
+        QamMapping::Gray => {
+            // decode the gray code MSB-first
+            let mut prev = 0;
+            let l = axis_bits.iter().fold(0i32, |l, b| {
+                prev ^= *b;
+                (l << 1) | i32::from(prev)
+            });
+            ((1 << bits) - 1) - 2 * l
+        }
+        QamMapping::ThreeGpp => {
+            // (1-2b0)(2^(k-1) - (1-2b2)(2^(k-2) - ... (1-2b_{2k-4})(2 - (1-2b_{2k-2}))))
+            let sign = |b: u8| 1 - 2 * i32::from(b);
+            let mut v = sign(axis_bits[bits - 1]);
+            for i in (1..bits).rev() {
+                v = sign(axis_bits[i - 1]) * ((1 << (bits - i)) - v);
+            }
+            v
+        }
+    }
+}
+
+/// Scale giving a square QAM with ```2^bits``` levels per axis unit average power
+pub(crate) fn norm(bits: usize) -> f32 {
+    let levels = (1 << bits) as f32;
+    // average power of both axes: 2 * (L^2 - 1) / 3
+    (2.0 * (levels * levels - 1.0) / 3.0).sqrt()
+}
+
+/// Fill a square QAM table with unit average power
+fn fill_square(table: &mut [cf32], mapping: QamMapping) {
+    let bits = axis_bits(table.len());
+    let norm = norm(bits);
+    for (idx, s) in table.iter_mut().enumerate() {
+        *s = cf32::new(
+            level(idx, bits, mapping, false) as f32,
+            level(idx, bits, mapping, true) as f32,
+        ) / norm;
+    }
+}
+
+/// Fast soft demodulator for the square QAM constellations
+/// ([qam16](qam16), [qam64](qam64), ...) with unit average power.
+///
+/// Since the in-phase and quadrature bits of square QAM are independent, the max-log LLRs
+/// are computed per axis from the ```2^(n/2)``` amplitude levels instead of all ```2^n``` symbols.
+/// The results equal those of [demod_llr_maxlog](crate::modulation::Modulation::demod_llr_maxlog).
+///
+/// # Example
+/// ```
+/// use aether_primitives::cf32;
+/// use aether_primitives::modulation::{qam64, Modulation, QamDemapper, QamMapping};
+///
+/// let m = qam64(QamMapping::ThreeGpp);
+/// let symbols = m.modulate(&[0, 1, 1, 0, 1, 1]);
+///
+/// let mut llrs = Vec::new();
+/// QamDemapper::new(6, QamMapping::ThreeGpp).llr_maxlog(&mut symbols.iter(), 0.01, &mut llrs);
+/// let bits = llrs.iter().map(|l| (*l < 0.0) as u8).collect::<Vec<_>>();
+/// assert_eq!(bits, vec![0, 1, 1, 0, 1, 1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct QamDemapper {
+    bits: usize,
+    mapping: QamMapping,
+    /// normalised amplitude of each axis bit pattern (bit i is the i-th bit of the specification)
+    levels: Vec<f32>,
+}
+
+impl QamDemapper {
+    /// Create a demapper for square QAM with ```bits_per_symbol``` bits (4 for 16-QAM, ...)
+    /// # Panics
+    /// Panics if ```bits_per_symbol``` is odd
+    pub fn new(bits_per_symbol: usize, mapping: QamMapping) -> QamDemapper {
+        let bits = axis_bits(1 << bits_per_symbol);
+        let norm = norm(bits);
+        let levels = (0..1usize << bits)
+            .map(|pattern| {
+                // build the symbol index which has this pattern on the in-phase axis
+                let idx = (0..bits)
+                    .map(|i| (pattern >> i & 1) << bit_pos(i, bits, mapping, false))
+                    .sum();
+                level(idx, bits, mapping, false) as f32 / norm
+            })
+            .collect();
+        QamDemapper {
+            bits,
+            mapping,
+            levels,
+        }
+    }
+
+    /// Max-log LLRs ```ln(P(b=0|y) / P(b=1|y))``` of the bits of each symbol
+    /// (see [demod_llr](crate::modulation::Modulation::demod_llr) for the conventions)
+    pub fn llr_maxlog<'a>(
+        &self,
+        symbols: &mut impl Iterator<Item = &'a cf32>,
+        noise_var: f32,
+        output: &mut Vec<f32>,
+    ) {
+        let scale = 0.5 / noise_var;
+        let mut llrs = vec![0f32; 2 * self.bits];
+        for s in symbols {
+            for (quadrature, y) in [(false, s.re), (true, s.im)].iter() {
+                for i in 0..self.bits {
+                    let (mut zero, mut one) = (f32::INFINITY, f32::INFINITY);
+                    for (pattern, a) in self.levels.iter().enumerate() {
+                        let d = (y - a) * (y - a);
+                        if pattern >> i & 1 == 0 {
+                            zero = zero.min(d);
+                        } else {
+                            one = one.min(d);
+                        }
+                    }
+                    llrs[bit_pos(i, self.bits, self.mapping, *quadrature)] = (one - zero) * scale;
+                }
+            }
+            output.extend_from_slice(&llrs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::modulation::{
+        qam1024, qam16, qam256, qam64, qpsk, Modulation, QamDemapper, QamMapping,
+    };
+    use crate::noise;
+    use assert_approx_eq::assert_approx_eq;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn power(table: &[cf32]) -> f32 {
+        table.iter().map(|s| s.norm_sqr()).sum::<f32>() / table.len() as f32
+    }
+
+    /// checks that the table has unit power, no duplicates
+    /// and that all nearest neighbours differ in exactly one bit
+    fn check_gray(table: &[cf32]) {
+        assert_approx_eq!(power(table), 1.0, 1e-5);
+        let min_dist = table
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| table[i + 1..].iter().map(move |b| (a - b).norm()))
+            .fold(f32::INFINITY, f32::min);
+        assert!(min_dist > 1e-3, "Constellation contains duplicates");
+
+        for (i, a) in table.iter().enumerate() {
+            for (j, b) in table.iter().enumerate() {
+                if i != j && (a - b).norm() < min_dist * 1.01 {
+                    assert_eq!((i ^ j).count_ones(), 1, "{} and {} are not gray", i, j);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gray() {
+        for mapping in &[QamMapping::Gray, QamMapping::ThreeGpp] {
+            check_gray(&qam16(*mapping));
+            check_gray(&qam64(*mapping));
+            check_gray(&qam256(*mapping));
+            check_gray(&qam1024(*mapping));
+        }
+
+        // 4-QAM equivalent to qpsk
+        let q = qpsk();
+        let g = qam16(QamMapping::Gray);
+        for idx in 0..4 {
+            // the inner points (b1 = b3 = 1) have the same signs as the QPSK points
+            let inner = g[(idx & 1) | (idx & 2) << 1 | 0b1010];
+            assert_eq!(inner.re.signum(), q[idx].re.signum());
+            assert_eq!(inner.im.signum(), q[idx].im.signum());
+        }
+    }
+
+    #[test]
+    fn three_gpp() {
+        // TS 38.211 Table 5.1.3-2 (excerpt, b0 is the first bit)
+        let n = 10f32.sqrt();
+        let m = qam16(QamMapping::ThreeGpp);
+        for (bits, re, im) in &[
+            ([0u8, 0, 0, 0], 1.0, 1.0),
+            ([0, 0, 1, 0], 3.0, 1.0),
+            ([0, 1, 0, 1], 1.0, -3.0),
+            ([1, 1, 1, 1], -3.0, -3.0),
+            ([1, 0, 0, 1], -1.0, 3.0),
+        ] {
+            assert_eq!(m[<[cf32; 16]>::index(bits)], cf32::new(*re, *im) / n);
+        }
+
+        // 64QAM: (1-2b0)(4-(1-2b2)(2-(1-2b4))) + j(1-2b1)(4-(1-2b3)(2-(1-2b5)))
+        let n = 42f32.sqrt();
+        let m = qam64(QamMapping::ThreeGpp);
+        for (bits, re, im) in &[
+            ([0u8, 0, 0, 0, 0, 0], 3.0, 3.0),
+            ([0, 0, 0, 0, 1, 1], 1.0, 1.0),
+            ([0, 1, 1, 0, 1, 0], 7.0, -3.0),
+            ([1, 0, 1, 1, 0, 1], -5.0, 7.0),
+        ] {
+            assert_eq!(m[<[cf32; 64]>::index(bits)], cf32::new(*re, *im) / n);
+        }
+
+        // 256QAM: outermost corner b0..b7 = 0,0,1,1,1,1,1,1 => (15 + 15j) / sqrt(170)
+        let m = qam256(QamMapping::ThreeGpp);
+        assert_approx_eq!(m[0b1111_1100].re, 15.0 / 170f32.sqrt());
+        assert_approx_eq!(m[0b1111_1100].im, 15.0 / 170f32.sqrt());
+
+        // 1024QAM: b0..b9 = 1,0,1,0,1,0,1,0,1,0 => (-31 + 11j) / sqrt(682)
+        let m = qam1024(QamMapping::ThreeGpp);
+        assert_approx_eq!(m[0b01_0101_0101].re, -31.0 / 682f32.sqrt());
+        assert_approx_eq!(m[0b01_0101_0101].im, 11.0 / 682f32.sqrt());
+    }
+
+    #[test]
+    fn naive_demod() {
+        let mut r = StdRng::seed_from_u64(815);
+        for mapping in &[QamMapping::Gray, QamMapping::ThreeGpp] {
+            let m = qam64(*mapping);
+            let bits = (0..600).map(|_| r.gen_range(0u8, 2u8)).collect::<Vec<_>>();
+            let symbols = m.modulate(&bits);
+            let mut demod = Vec::with_capacity(600);
+            m.demod_naive(&mut symbols.iter(), &mut demod);
+            assert_eq!(bits, demod);
+
+            let m = qam16(*mapping);
+            let symbols = m.modulate(&bits);
+            demod.clear();
+            m.demod_naive(&mut symbols.iter(), &mut demod);
+            assert_eq!(bits, demod);
+        }
+    }
+
+    #[test]
+    fn hard_demod_1024() {
+        let mut r = StdRng::seed_from_u64(4711);
+        let m = qam1024(QamMapping::ThreeGpp);
+        let bits = (0..1000).map(|_| r.gen_range(0u8, 2u8)).collect::<Vec<_>>();
+        let symbols = m.modulate(&bits);
+        let mut demod = Vec::with_capacity(1000);
+        m.demod_naive(&mut symbols.iter(), &mut demod);
+        assert_eq!(bits, demod);
+    }
+
+    #[test]
+    fn llr() {
+        let mut r = StdRng::seed_from_u64(815);
+        let noise_var = 0.01;
+        let mut awgn = noise::new(noise_var, 4711);
+
+        for mapping in &[QamMapping::Gray, QamMapping::ThreeGpp] {
+            let m = qam16(*mapping);
+            let bits = (0..4000).map(|_| r.gen_range(0u8, 2u8)).collect::<Vec<_>>();
+            let symbols = m
+                .modulate(&bits)
+                .iter()
+                .zip(awgn.iter())
+                .map(|(s, n)| s + n)
+                .collect::<Vec<_>>();
+
+            let (mut exact, mut maxlog, mut fast) = (Vec::new(), Vec::new(), Vec::new());
+            m.demod_llr(&mut symbols.iter(), noise_var, &mut exact);
+            m.demod_llr_maxlog(&mut symbols.iter(), noise_var, &mut maxlog);
+            QamDemapper::new(4, *mapping).llr_maxlog(&mut symbols.iter(), noise_var, &mut fast);
+            assert_eq!(exact.len(), bits.len());
+
+            let mut hard = Vec::new();
+            m.demod_naive(&mut symbols.iter(), &mut hard);
+            for (i, ((e, ml), f)) in exact.iter().zip(&maxlog).zip(&fast).enumerate() {
+                assert!(
+                    (ml - f).abs() <= 1e-3 * ml.abs().max(1.0),
+                    "{}: {} != {}",
+                    i,
+                    ml,
+                    f
+                );
+                // max-log decisions are the hard decisions
+                assert_eq!((*ml < 0.0) as u8, hard[i]);
+                // the exact LLR is never more confident than max-log by more than ln(#symbols/2)
+                if ml.abs() > 2.0 * 8f32.ln() {
+                    assert_eq!(e.signum(), ml.signum());
+                }
+                assert!((e - ml).abs() <= 8f32.ln() + 1e-3);
+            }
+
+            // at this SNR (~17dB) almost all decisions should be correct
+            let errors = hard.iter().zip(&bits).filter(|(h, b)| h != b).count();
+            assert!(errors < 40, "{} bit errors", errors);
+        }
+    }
+
+    #[test]
+    fn bpsk_llr() {
+        // LLR of BPSK: 2 (re + im) / noise_var for the generic (1 + j) / (-1 - j) table
+        let m = crate::modulation::bpsk();
+        let y = [cf32::new(0.3, -0.1), cf32::new(-2.0, 0.5)];
+        let (mut exact, mut maxlog) = (Vec::new(), Vec::new());
+        m.demod_llr(&mut y.iter(), 0.5, &mut exact);
+        m.demod_llr_maxlog(&mut y.iter(), 0.5, &mut maxlog);
+        assert_approx_eq!(exact[0], 0.8, 1e-5);
+        assert_approx_eq!(exact[1], -6.0, 1e-5);
+        assert_eq!(exact, maxlog);
+    }
+}
@@ -0,0 +1,191 @@
+use super::qam::{self, QamMapping};
+use crate::cf32;
+use std::f32::consts::PI;
+
+/// Hard demodulator deciding on the decision regions of a constellation directly
+/// instead of computing the distance to every symbol like
+/// [demod_naive](crate::modulation::Modulation::demod_naive).
+///
+/// - Square QAM (as created by [qam16](crate::modulation::qam16), ...): the in-phase and
+///   quadrature amplitude are quantised to the nearest level separately
+/// - Gray-coded PSK (as created by [psk8](crate::modulation::psk8), ...):
+///   the phase is quantised to the nearest point
+///
+/// Both take constant time per symbol regardless of the constellation size.
+/// The results equal those of ```demod_naive``` (except for ties on the region boundaries).
+///
+/// # Example
+/// ```
+/// use aether_primitives::modulation::{qam256, Modulation, QamMapping, Slicer};
+///
+/// let m = qam256(QamMapping::ThreeGpp);
+/// let bits = vec![1, 0, 0, 1, 1, 1, 0, 1, 0, 0, 0, 0, 0, 1, 1, 0];
+/// let symbols = m.modulate(&bits);
+///
+/// let mut demod = Vec::new();
+/// Slicer::qam(8, QamMapping::ThreeGpp).demod(&mut symbols.iter(), &mut demod);
+/// assert_eq!(bits, demod);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Slicer {
+    bits_per_symbol: usize,
+    kind: Kind,
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+    Qam {
+        /// number of amplitude levels per axis
+        levels: usize,
+        /// scale from unit power to the odd integer grid
+        norm: f32,
+        /// index bits contributed by each in-phase level (ordered from lowest to highest amplitude)
+        inphase: Vec<usize>,
+        /// index bits contributed by each quadrature level
+        quadrature: Vec<usize>,
+    },
+    Psk {
+        offset: f32,
+    },
+}
+
+impl Slicer {
+    /// Slicer for square QAM with unit average power and ```bits_per_symbol``` bits (4 for 16-QAM, ...)
+    /// Also covers the generic [qpsk](crate::modulation::qpsk) using [QamMapping::Gray](QamMapping::Gray)
+    /// # Panics
+    /// Panics if ```bits_per_symbol``` is odd
+    pub fn qam(bits_per_symbol: usize, mapping: QamMapping) -> Slicer {
+        let bits = qam::axis_bits(1 << bits_per_symbol);
+        let levels = 1 << bits;
+        let axis = |quadrature: bool| {
+            let mut table = vec![0; levels];
+            for idx in 0..1usize << bits_per_symbol {
+                let l = qam::level(idx, bits, mapping, quadrature);
+                let pos = ((l + levels as i32 - 1) / 2) as usize;
+                // only keep the bits of this axis
+                table[pos] = (0..bits)
+                    .map(|i| qam::bit_pos(i, bits, mapping, quadrature))
+                    .map(|p| idx & 1 << p)
+                    .sum();
+            }
+            table
+        };
+        Slicer {
+            bits_per_symbol,
+            kind: Kind::Qam {
+                levels,
+                norm: qam::norm(bits),
+                inphase: axis(false),
+                quadrature: axis(true),
+            },
+        }
+    }
+
+    /// Slicer for Gray-coded PSK with ```bits_per_symbol``` bits (3 for 8-PSK, ...)
+    /// and the given phase offset (radians)
+    pub fn psk(bits_per_symbol: usize, offset: f32) -> Slicer {
+        Slicer {
+            bits_per_symbol,
+            kind: Kind::Psk { offset },
+        }
+    }
+
+    /// Table index of the symbol closest to ```s```
+    #[inline]
+    pub fn index(&self, s: cf32) -> usize {
+        match &self.kind {
+            Kind::Qam {
+                levels,
+                norm,
+                inphase,
+                quadrature,
+            } => {
+                // map the odd integer grid -(L-1)..=L-1 to 0..L
+                let pos = |x: f32| {
+                    let p = ((x * norm + (*levels - 1) as f32) / 2.0).round();
+                    p.max(0.0).min((*levels - 1) as f32) as usize
+                };
+                inphase[pos(s.re)] | quadrature[pos(s.im)]
+            }
+            Kind::Psk { offset } => {
+                let m = 1usize << self.bits_per_symbol;
+                let p = ((s.arg() - offset) * m as f32 / (2.0 * PI)).round() as i64;
+                let p = p.rem_euclid(m as i64) as usize;
+                p ^ (p >> 1)
+            }
+        }
+    }
+
+    /// Hard demodulate the symbols, pushing one byte per bit to ```output```
+    /// (same conventions as [demod_naive](crate::modulation::Modulation::demod_naive))
+    pub fn demod<'a>(&self, symbols: &mut impl Iterator<Item = &'a cf32>, output: &mut Vec<u8>) {
+        for s in symbols {
+            let idx = self.index(*s);
+            output.extend((0..self.bits_per_symbol).map(|i| (idx >> i & 1) as u8));
+        }
+    }
+
+    /// Number of bits demodulated from one symbol
+    pub fn bits_per_symbol(&self) -> usize {
+        self.bits_per_symbol
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::modulation::{
+        psk16, psk8, qam1024, qam16, qam64, qpsk, Modulation, QamMapping, Slicer,
+    };
+    use crate::noise;
+    use crate::util::random_bits;
+
+    /// checks the slicer against modulate (noiseless) and demod_naive (noisy)
+    fn check<M: Modulation>(m: &M, slicer: &Slicer) {
+        let bits = random_bits(M::BITS_PER_SYMBOL * 500, 815);
+        let mut symbols = m.modulate(&bits);
+
+        let mut demod = Vec::new();
+        slicer.demod(&mut symbols.iter(), &mut demod);
+        assert_eq!(bits, demod);
+
+        noise::new(0.05, 4711).apply(&mut symbols);
+        let (mut naive, mut sliced) = (Vec::new(), Vec::new());
+        m.demod_naive(&mut symbols.iter(), &mut naive);
+        slicer.demod(&mut symbols.iter(), &mut sliced);
+        assert_eq!(naive, sliced);
+    }
+
+    #[test]
+    fn qam() {
+        for mapping in &[QamMapping::Gray, QamMapping::ThreeGpp] {
+            check(&qam16(*mapping), &Slicer::qam(4, *mapping));
+            check(&qam64(*mapping), &Slicer::qam(6, *mapping));
+            check(&qam1024(*mapping), &Slicer::qam(10, *mapping));
+        }
+        // the generic qpsk table is not normalised, which does not matter for a single level
+        check(&qpsk(), &Slicer::qam(2, QamMapping::Gray));
+
+        // points outside the constellation are clipped to the outermost level
+        let s = Slicer::qam(4, QamMapping::Gray);
+        assert_eq!(s.index(cf32::new(100.0, -100.0)), 0b0100);
+        assert!(qam16(QamMapping::Gray)[0b0100].re > 0.9);
+    }
+
+    #[test]
+    fn psk() {
+        check(&psk8(0.0), &Slicer::psk(3, 0.0));
+        check(&psk8(0.4), &Slicer::psk(3, 0.4));
+        check(&psk16(-1.0), &Slicer::psk(4, -1.0));
+    }
+
+    #[test]
+    fn qpsk_demod() {
+        // the specialised demod_naive of QPSK
+        let m = qpsk();
+        let bits = random_bits(1000, 18324357);
+        let mut demod = Vec::new();
+        m.demod_naive(&mut m.modulate(&bits).iter(), &mut demod);
+        assert_eq!(bits, demod);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::cf32;
+use rand::prelude::*;
+use std::f64::consts::PI;
+
+/// 2^32: the phase accumulator wraps around once per full turn
+const TURN: f64 = 4_294_967_296f64;
+
+/// A Numerically Controlled Oscillator generating a complex tone ```exp(j*2*pi*f/fs*n)```
+/// based on a 32 bit phase accumulator.
+///
+/// Samples are computed exactly (using sin/cos) by default.
+/// Alternatively a lookup table of 2^bits entries may be used, which trades accuracy
+/// for speed. The quantisation of the phase to the table size causes spurs, which
+/// can be spread into the noise floor by enabling phase dithering.
+///
+/// # Example
+/// ```
+/// use aether_primitives::{cf32, assert_evm};
+/// use aether_primitives::nco::Nco;
+///
+/// // a 1 kHz tone sampled at 8 kHz
+/// let mut nco = Nco::new(1000.0, 8000.0);
+/// let mut tone = vec![cf32::default(); 8];
+/// nco.fill(&mut tone);
+///
+/// let correct = (0..8)
+///     .map(|n| cf32::from_polar(&1.0, &(std::f32::consts::PI / 4.0 * n as f32)))
+///     .collect::<Vec<_>>();
+/// assert_evm!(&tone, &correct, -60.0);
+///
+/// // mix a signal up by 1kHz; the phase carries over to the next call
+/// let mut signal = vec![cf32::new(1.0, 0.0); 8];
+/// nco.mix(&mut signal);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Nco {
+    /// current phase; a full turn is 2^32
+    phase: u32,
+    /// phase increment per sample
+    step: u32,
+    /// optional lookup table and the shift to get from accumulator to table index
+    lut: Option<(Vec<cf32>, u32)>,
+    /// rng used for phase dithering
+    dither: Option<StdRng>,
+}
+
+impl Nco {
+    /// Create an oscillator with the frequency ```freq``` (may be negative)
+    /// for the given sample rate, starting at phase 0
+    pub fn new(freq: f32, sample_rate: f32) -> Nco {
+        Nco {
+            phase: 0,
+            step: step(freq, sample_rate),
+            lut: None,
+            dither: None,
+        }
+    }
+
+    /// Create an oscillator which reads its samples from a lookup table with ```2^bits``` entries
+    /// # Panics
+    /// Panics if ```bits``` is not in 1..=24
+    pub fn with_lut(freq: f32, sample_rate: f32, bits: u32) -> Nco {
+        assert!(
+            (1..=24).contains(&bits),
+            "Lookup tables must have between 2^1 and 2^24 entries"
+        );
+        let len = 1usize << bits;
+        let lut = (0..len)
+            .map(|i| {
+                let (s, c) = (2.0 * PI * i as f64 / len as f64).sin_cos();
+                cf32::new(c as f32, s as f32)
+            })
+            .collect();
+
+        Nco {
+            phase: 0,
+            step: step(freq, sample_rate),
+            lut: Some((lut, 32 - bits)),
+            dither: None,
+        }
+    }
+
+    /// Enable phase dithering using an rng with the given seed.
+    /// Before each table lookup a uniformly distributed offset of up to one table step
+    /// is added to the phase. This has no effect on oscillators without a lookup table.
+    pub fn dithered(mut self, seed: u64) -> Nco {
+        self.dither = Some(SeedableRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Change the frequency while keeping the current phase
+    pub fn set_freq(&mut self, freq: f32, sample_rate: f32) {
+        self.step = step(freq, sample_rate);
+    }
+
+    /// The current phase in radians (in -pi..pi)
+    pub fn phase(&self) -> f32 {
+        (f64::from(self.phase as i32) / TURN * 2.0 * PI) as f32
+    }
+
+    /// Set the current phase (in radians)
+    pub fn set_phase(&mut self, phase: f32) {
+        let turns = (f64::from(phase) / (2.0 * PI)).rem_euclid(1.0);
+        self.phase = (turns * TURN).round() as u64 as u32;
+    }
+
+    /// Overwrite ```out``` with samples of this oscillator
+    pub fn fill(&mut self, out: &mut [cf32]) {
+        out.iter_mut().zip(self).for_each(|(o, s)| *o = s);
+    }
+
+    /// Multiply ```signal``` with samples of this oscillator
+    /// i.e. shift it by the frequency of this oscillator
+    pub fn mix(&mut self, signal: &mut [cf32]) {
+        signal.iter_mut().zip(self).for_each(|(o, s)| *o *= s);
+    }
+}
+
+impl Iterator for Nco {
+    type Item = cf32;
+
+    #[inline]
+    fn next(&mut self) -> Option<cf32> {
+        let sample = match (&self.lut, &mut self.dither) {
+            (None, _) => {
+                let (s, c) = (f64::from(self.phase) / TURN * 2.0 * PI).sin_cos();
+                cf32::new(c as f32, s as f32)
+            }
+            (Some((lut, shift)), None) => lut[(self.phase >> shift) as usize],
+            (Some((lut, shift)), Some(rng)) => {
+                let offset = rng.gen::<u32>() >> (32 - shift);
+                lut[(self.phase.wrapping_add(offset) >> shift) as usize]
+            }
+        };
+        self.phase = self.phase.wrapping_add(self.step);
+        Some(sample)
+    }
+}
+
+/// phase increment per sample for the given frequency
+fn step(freq: f32, sample_rate: f32) -> u32 {
+    let turns = (f64::from(freq) / f64::from(sample_rate)).rem_euclid(1.0);
+    (turns * TURN).round() as u64 as u32
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::nco::Nco;
+    use crate::vecops::VecOps;
+    use assert_approx_eq::assert_approx_eq;
+    use std::f64::consts::PI;
+
+    fn tone(freq: f64, sample_rate: f64, len: usize) -> Vec<cf32> {
+        (0..len)
+            .map(|n| {
+                let (s, c) = (2.0 * PI * freq / sample_rate * n as f64).sin_cos();
+                cf32::new(c as f32, s as f32)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn exact() {
+        for freq in &[1000f32, -2500.0, 0.0, 11_000.0] {
+            let mut nco = Nco::new(*freq, 48_000.0);
+            let mut out = vec![cf32::default(); 10_000];
+            nco.fill(&mut out);
+            // the step is rounded to 2^-32 turns, so the phase error slowly grows over time
+            assert_evm!(&out, &tone(f64::from(*freq), 48_000.0, 10_000), -50.0);
+        }
+    }
+
+    #[test]
+    fn lut() {
+        let correct = tone(1234.0, 48_000.0, 4096);
+        let mut out = vec![cf32::default(); 4096];
+
+        // the phase error of a table lookup is below one table step (2*pi/4096 ~ -28dB)
+        Nco::with_lut(1234.0, 48_000.0, 12).fill(&mut out);
+        assert_evm!(&out, &correct, -25.0);
+
+        Nco::with_lut(1234.0, 48_000.0, 12)
+            .dithered(815)
+            .fill(&mut out);
+        assert_evm!(&out, &correct, -25.0);
+    }
+
+    #[test]
+    fn phase() {
+        let mut nco = Nco::new(1000.0, 8000.0);
+        assert_approx_eq!(nco.phase(), 0.0);
+        nco.nth(1);
+        assert_approx_eq!(nco.phase(), std::f32::consts::FRAC_PI_2);
+        // the phase wraps around at pi
+        nco.nth(1);
+        assert_approx_eq!(nco.phase(), -std::f32::consts::PI);
+
+        nco.set_phase(-std::f32::consts::FRAC_PI_4);
+        assert_approx_eq!(nco.phase(), -std::f32::consts::FRAC_PI_4);
+        let s = nco.next().unwrap();
+        assert_evm!(&[s], &[cf32::new(0.5f32.sqrt(), -(0.5f32.sqrt()))]);
+    }
+
+    #[test]
+    fn freq_shift() {
+        let (freq, fs) = (-1500f32, 20_000f32);
+        let signal = tone(200.0, f64::from(fs), 1000);
+        let correct = tone(200.0 + f64::from(freq), f64::from(fs), 1000);
+
+        // shifting in blocks must be phase continuous
+        let mut shifted = signal.clone();
+        let mut phase = 0f32;
+        for block in shifted.chunks_mut(128) {
+            block.vec_freq_shift(freq, fs, &mut phase);
+        }
+        assert_evm!(&shifted, &correct, -60.0);
+
+        let mut nco = Nco::new(freq, fs);
+        let mut mixed = signal.clone();
+        nco.mix(&mut mixed);
+        assert_evm!(&mixed, &correct, -60.0);
+    }
+}
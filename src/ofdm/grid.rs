@@ -0,0 +1,574 @@
+use super::Ofdm;
+use crate::cf32;
+
+/// Number of subcarriers in a physical resource block (PRB)
+pub const SC_PER_PRB: usize = 12;
+
+/// Radio access technology of a [Numerology](Numerology)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rat {
+    /// LTE (3GPP TS 36.211): the DC subcarrier is left empty
+    Lte,
+    /// 5G NR (3GPP TS 38.211): the DC subcarrier is used
+    Nr,
+}
+
+/// 5G NR frequency range of a [Numerology](Numerology), which determines the bandwidth
+/// configurations (LTE numerologies use ```Fr1```)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrequencyRange {
+    /// FR1 (sub-6 GHz, TS 38.101-1)
+    Fr1,
+    /// FR2 (mmWave, TS 38.101-2)
+    Fr2,
+}
+
+/// Cyclic prefix length of a [Numerology](Numerology)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CyclicPrefix {
+    /// 14 symbols per 1ms subframe (times ```2^mu```)
+    Normal,
+    /// 12 symbols per 1ms subframe (times ```2^mu```)
+    Extended,
+}
+
+/// Transmission bandwidth configurations: channel bandwidth (MHz) => number of PRBs
+/// LTE: TS 36.101 Table 5.6-1
+const LTE_PRBS: [(f32, usize); 6] = [
+    (1.4, 6),
+    (3.0, 15),
+    (5.0, 25),
+    (10.0, 50),
+    (15.0, 75),
+    (20.0, 100),
+];
+/// NR FR1, 15 kHz: TS 38.101-1 Table 5.3.2-1
+const NR_PRBS_15: [(f32, usize); 10] = [
+    (5.0, 25),
+    (10.0, 52),
+    (15.0, 79),
+    (20.0, 106),
+    (25.0, 133),
+    (30.0, 160),
+    (35.0, 188),
+    (40.0, 216),
+    (45.0, 242),
+    (50.0, 270),
+];
+/// NR FR1, 30 kHz: TS 38.101-1 Table 5.3.2-1
+const NR_PRBS_30: [(f32, usize); 15] = [
+    (5.0, 11),
+    (10.0, 24),
+    (15.0, 38),
+    (20.0, 51),
+    (25.0, 65),
+    (30.0, 78),
+    (35.0, 92),
+    (40.0, 106),
+    (45.0, 119),
+    (50.0, 133),
+    (60.0, 162),
+    (70.0, 189),
+    (80.0, 217),
+    (90.0, 245),
+    (100.0, 273),
+];
+/// NR FR1, 60 kHz: TS 38.101-1 Table 5.3.2-1
+const NR_PRBS_60: [(f32, usize); 14] = [
+    (10.0, 11),
+    (15.0, 18),
+    (20.0, 24),
+    (25.0, 31),
+    (30.0, 38),
+    (35.0, 44),
+    (40.0, 51),
+    (45.0, 58),
+    (50.0, 65),
+    (60.0, 79),
+    (70.0, 93),
+    (80.0, 107),
+    (90.0, 121),
+    (100.0, 135),
+];
+/// NR FR2, 60 kHz: TS 38.101-2 Table 5.3.2-1
+const NR_FR2_PRBS_60: [(f32, usize); 3] = [(50.0, 66), (100.0, 132), (200.0, 264)];
+/// NR FR2, 120 kHz: TS 38.101-2 Table 5.3.2-1
+const NR_PRBS_120: [(f32, usize); 4] = [(50.0, 32), (100.0, 66), (200.0, 132), (400.0, 264)];
+
+/// OFDM numerology of LTE or 5G NR: subcarrier spacing ```15 kHz * 2^mu```,
+/// symbols per subframe and cyclic prefix lengths.
+///
+/// Sizes in samples are given for an arbitrary FFT size ```N``` (at least 128), i.e.
+/// a sample rate of ```N * scs```. The normal cyclic prefix is ```144/2048 * N``` samples long,
+/// except for the first symbol of each 0.5ms which is ```16 * 2^mu / 2048 * N``` samples longer
+/// (i.e. 160 instead of 144 for 15 kHz), the extended one is ```512/2048 * N```.
+///
+/// # Example
+/// ```
+/// use aether_primitives::ofdm::grid::{FrequencyRange, Numerology};
+///
+/// // LTE 20 MHz
+/// let lte = Numerology::lte();
+/// let prbs = lte.prbs(20.0).unwrap();
+/// assert_eq!(prbs, 100);
+/// assert_eq!(lte.fft_size(prbs), 2048);
+/// assert_eq!(lte.sample_rate(2048), 30.72e6);
+/// assert_eq!(lte.subframe_len(2048), 30720);
+///
+/// // NR 100 MHz at 30 kHz
+/// let nr = Numerology::nr(FrequencyRange::Fr1, 1);
+/// assert_eq!(nr.prbs(100.0), Some(273));
+/// assert_eq!(nr.fft_size(273), 4096);
+///
+/// // 60 kHz defines different bandwidth configurations in FR1 and FR2
+/// let fr1 = Numerology::nr(FrequencyRange::Fr1, 2);
+/// let fr2 = Numerology::nr(FrequencyRange::Fr2, 2);
+/// assert_eq!(fr1.prbs(100.0), Some(135));
+/// assert_eq!(fr2.prbs(100.0), Some(132));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Numerology {
+    rat: Rat,
+    fr: FrequencyRange,
+    mu: u32,
+    cp: CyclicPrefix,
+}
+
+impl Numerology {
+    /// LTE with the normal cyclic prefix (15 kHz subcarrier spacing)
+    pub fn lte() -> Numerology {
+        Numerology {
+            rat: Rat::Lte,
+            fr: FrequencyRange::Fr1,
+            mu: 0,
+            cp: CyclicPrefix::Normal,
+        }
+    }
+
+    /// LTE with the extended cyclic prefix (15 kHz subcarrier spacing)
+    pub fn lte_extended() -> Numerology {
+        Numerology {
+            rat: Rat::Lte,
+            fr: FrequencyRange::Fr1,
+            mu: 0,
+            cp: CyclicPrefix::Extended,
+        }
+    }
+
+    /// NR in the given frequency range with the normal cyclic prefix and a subcarrier spacing
+    /// of ```15 kHz * 2^mu```
+    /// # Panics
+    /// Panics if ```mu``` is not defined for the frequency range
+    /// (FR1: ```0..=2```, 15 to 60 kHz; FR2: ```2..=4```, 60 to 240 kHz)
+    pub fn nr(fr: FrequencyRange, mu: u32) -> Numerology {
+        let valid = match fr {
+            FrequencyRange::Fr1 => mu <= 2,
+            FrequencyRange::Fr2 => (2..=4).contains(&mu),
+        };
+        assert!(valid, "NR supports mu = 0..=2 in FR1 and mu = 2..=4 in FR2");
+        Numerology {
+            rat: Rat::Nr,
+            fr,
+            mu,
+            cp: CyclicPrefix::Normal,
+        }
+    }
+
+    /// NR (FR1) with the extended cyclic prefix, which is only defined for 60 kHz (```mu = 2```)
+    pub fn nr_extended() -> Numerology {
+        Numerology {
+            rat: Rat::Nr,
+            fr: FrequencyRange::Fr1,
+            mu: 2,
+            cp: CyclicPrefix::Extended,
+        }
+    }
+
+    /// The radio access technology
+    pub fn rat(&self) -> Rat {
+        self.rat
+    }
+
+    /// The frequency range
+    pub fn fr(&self) -> FrequencyRange {
+        self.fr
+    }
+
+    /// The numerology ```mu```
+    pub fn mu(&self) -> u32 {
+        self.mu
+    }
+
+    /// The cyclic prefix length
+    pub fn cp(&self) -> CyclicPrefix {
+        self.cp
+    }
+
+    /// Subcarrier spacing in Hz
+    pub fn scs(&self) -> f32 {
+        15e3 * (1 << self.mu) as f32
+    }
+
+    /// Number of OFDM symbols per slot (LTE: 7 or 6, NR: 14 or 12)
+    pub fn symbols_per_slot(&self) -> usize {
+        let symbols = match self.cp {
+            CyclicPrefix::Normal => 14,
+            CyclicPrefix::Extended => 12,
+        };
+        match self.rat {
+            Rat::Lte => symbols / 2,
+            Rat::Nr => symbols,
+        }
+    }
+
+    /// Number of OFDM symbols per 1ms subframe
+    pub fn symbols_per_subframe(&self) -> usize {
+        match self.cp {
+            CyclicPrefix::Normal => 14 << self.mu,
+            CyclicPrefix::Extended => 12 << self.mu,
+        }
+    }
+
+    /// Number of PRBs of the given channel bandwidth (MHz),
+    /// ```None``` if the bandwidth is not defined for this numerology
+    pub fn prbs(&self, bandwidth: f32) -> Option<usize> {
+        let table: &[(f32, usize)] = match (self.rat, self.fr, self.mu) {
+            (Rat::Lte, _, _) => &LTE_PRBS,
+            (Rat::Nr, FrequencyRange::Fr1, 0) => &NR_PRBS_15,
+            (Rat::Nr, FrequencyRange::Fr1, 1) => &NR_PRBS_30,
+            (Rat::Nr, FrequencyRange::Fr1, 2) => &NR_PRBS_60,
+            (Rat::Nr, FrequencyRange::Fr2, 2) => &NR_FR2_PRBS_60,
+            (Rat::Nr, FrequencyRange::Fr2, 3) => &NR_PRBS_120,
+            _ => &[],
+        };
+        table
+            .iter()
+            .find(|(bw, _)| (bw - bandwidth).abs() < 1e-3)
+            .map(|(_, n)| *n)
+    }
+
+    /// Smallest power of two FFT size for which the ```prbs``` occupy at most 85% of the bins
+    pub fn fft_size(&self, prbs: usize) -> usize {
+        let min = (prbs * SC_PER_PRB) as f32 / 0.85;
+        (min.ceil() as usize).next_power_of_two()
+    }
+
+    /// Sample rate in Hz for the given FFT size
+    pub fn sample_rate(&self, fft_size: usize) -> f32 {
+        self.scs() * fft_size as f32
+    }
+
+    /// Cyclic prefix length (samples) of symbol ```l``` within a subframe
+    pub fn cp_len(&self, l: usize, fft_size: usize) -> usize {
+        match self.cp {
+            CyclicPrefix::Extended => 512 * fft_size / 2048,
+            CyclicPrefix::Normal => {
+                if l % (7 << self.mu) == 0 {
+                    (144 + (16 << self.mu)) * fft_size / 2048
+                } else {
+                    144 * fft_size / 2048
+                }
+            }
+        }
+    }
+
+    /// Cyclic prefix lengths of all symbols of a subframe, e.g. for [Ofdm::with_cp](Ofdm::with_cp)
+    pub fn cp_lengths(&self, fft_size: usize) -> Vec<usize> {
+        (0..self.symbols_per_subframe())
+            .map(|l| self.cp_len(l, fft_size))
+            .collect()
+    }
+
+    /// Number of samples of a 1ms subframe
+    pub fn subframe_len(&self, fft_size: usize) -> usize {
+        self.cp_lengths(fft_size).iter().sum::<usize>() + self.symbols_per_subframe() * fft_size
+    }
+
+    /// OFDM modem for ```prbs``` with this numerology's FFT size, cyclic prefix and DC handling
+    pub fn ofdm(&self, prbs: usize) -> Ofdm {
+        let fft_size = self.fft_size(prbs);
+        let ofdm = Ofdm::new(fft_size, prbs * SC_PER_PRB).with_cp(self.cp_lengths(fft_size));
+        match self.rat {
+            Rat::Lte => ofdm,
+            Rat::Nr => ofdm.with_dc(),
+        }
+    }
+}
+
+/// Resource grid of ```prbs * 12``` subcarriers times a number of OFDM symbols.
+/// Resource elements are addressed by subcarrier ```k``` (from the lowest frequency)
+/// and symbol ```l```.
+///
+/// # Example
+/// ```
+/// use aether_primitives::cf32;
+/// use aether_primitives::ofdm::grid::{Numerology, ResourceGrid};
+///
+/// let num = Numerology::lte();
+/// let mut grid = ResourceGrid::new(6, num.symbols_per_subframe());
+/// // a reference signal on every 6th subcarrier of symbol 0
+/// let mapped = grid.map(&[cf32::new(1.0, 0.0); 12], |k, l| l == 0 && k % 6 == 0);
+/// assert_eq!(mapped, 12);
+///
+/// let samples = grid.to_time(&num);
+/// assert_eq!(samples.len(), num.subframe_len(128));
+/// let rx = ResourceGrid::from_time(&samples, &num, 6);
+/// assert!((rx[(6, 0)] - cf32::new(1.0, 0.0)).norm() < 1e-5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceGrid {
+    prbs: usize,
+    symbols: usize,
+    /// symbol-major: re[l * subcarriers + k]
+    re: Vec<cf32>,
+}
+
+impl ResourceGrid {
+    /// Create an empty (zeroed) grid
+    pub fn new(prbs: usize, symbols: usize) -> ResourceGrid {
+        ResourceGrid {
+            prbs,
+            symbols,
+            re: vec![cf32::default(); prbs * SC_PER_PRB * symbols],
+        }
+    }
+
+    /// Number of PRBs
+    pub fn prbs(&self) -> usize {
+        self.prbs
+    }
+
+    /// Number of subcarriers
+    pub fn subcarriers(&self) -> usize {
+        self.prbs * SC_PER_PRB
+    }
+
+    /// Number of OFDM symbols
+    pub fn symbols(&self) -> usize {
+        self.symbols
+    }
+
+    /// The resource elements of symbol ```l```
+    pub fn symbol(&self, l: usize) -> &[cf32] {
+        let n = self.subcarriers();
+        &self.re[l * n..(l + 1) * n]
+    }
+
+    /// The resource elements of symbol ```l```
+    pub fn symbol_mut(&mut self, l: usize) -> &mut [cf32] {
+        let n = self.subcarriers();
+        &mut self.re[l * n..(l + 1) * n]
+    }
+
+    /// Map ```values``` to the resource elements for which ```select(k, l)``` is true in
+    /// 3GPP order (subcarriers first, then symbols) until the values are exhausted.
+    /// Returns the number of mapped values.
+    pub fn map(&mut self, values: &[cf32], mut select: impl FnMut(usize, usize) -> bool) -> usize {
+        let n = self.subcarriers();
+        let mut values = values.iter();
+        let mut mapped = 0;
+        for (i, re) in self.re.iter_mut().enumerate() {
+            if !select(i % n, i / n) {
+                continue;
+            }
+            match values.next() {
+                Some(v) => *re = *v,
+                None => break,
+            }
+            mapped += 1;
+        }
+        mapped
+    }
+
+    /// Append the resource elements for which ```select(k, l)``` is true to ```output```
+    /// in 3GPP order (subcarriers first, then symbols)
+    pub fn extract(&self, output: &mut Vec<cf32>, mut select: impl FnMut(usize, usize) -> bool) {
+        let n = self.subcarriers();
+        output.extend(
+            self.re
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| select(i % n, i / n))
+                .map(|(_, re)| *re),
+        );
+    }
+
+    /// Resource elements of PRB ```prb``` in symbol ```l```: a selector for [map](ResourceGrid::map)
+    /// and [extract](ResourceGrid::extract)
+    pub fn in_prb(prb: usize, l: usize) -> impl Fn(usize, usize) -> bool {
+        move |k, s| s == l && k / SC_PER_PRB == prb
+    }
+
+    /// OFDM modulate the grid, starting at the first symbol of a subframe
+    pub fn to_time(&self, numerology: &Numerology) -> Vec<cf32> {
+        let mut out = Vec::new();
+        numerology.ofdm(self.prbs).modulate(&self.re, &mut out);
+        out
+    }
+
+    /// OFDM demodulate as many whole symbols from ```samples``` as possible into a grid of ```prbs```,
+    /// starting at the first symbol of a subframe
+    pub fn from_time(samples: &[cf32], numerology: &Numerology, prbs: usize) -> ResourceGrid {
+        let (mut re, mut pilots) = (Vec::new(), Vec::new());
+        numerology.ofdm(prbs).demod(samples, &mut re, &mut pilots);
+        ResourceGrid {
+            prbs,
+            symbols: re.len() / (prbs * SC_PER_PRB),
+            re,
+        }
+    }
+}
+
+impl std::ops::Index<(usize, usize)> for ResourceGrid {
+    type Output = cf32;
+
+    /// Resource element of subcarrier ```k``` and symbol ```l```
+    fn index(&self, (k, l): (usize, usize)) -> &cf32 {
+        assert!(k < self.subcarriers(), "Subcarrier out of range");
+        &self.re[l * self.subcarriers() + k]
+    }
+}
+
+impl std::ops::IndexMut<(usize, usize)> for ResourceGrid {
+    fn index_mut(&mut self, (k, l): (usize, usize)) -> &mut cf32 {
+        assert!(k < self.subcarriers(), "Subcarrier out of range");
+        let n = self.subcarriers();
+        &mut self.re[l * n + k]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::fft::{Cfft, Fft, Scale};
+    use crate::ofdm::grid::{FrequencyRange, Numerology, ResourceGrid};
+
+    #[test]
+    fn numerology() {
+        let lte = Numerology::lte();
+        let sizes = [
+            (1.4, 128),
+            (3.0, 256),
+            (5.0, 512),
+            (10.0, 1024),
+            (20.0, 2048),
+        ];
+        for (bw, fft) in sizes.iter() {
+            assert_eq!(lte.fft_size(lte.prbs(*bw).unwrap()), *fft);
+            // 1ms at the LTE sample rate
+            assert_eq!(lte.subframe_len(*fft), 15 * *fft);
+        }
+        assert_eq!(lte.symbols_per_slot(), 7);
+        assert_eq!(
+            lte.cp_lengths(2048)[..8],
+            [160, 144, 144, 144, 144, 144, 144, 160]
+        );
+        assert_eq!(lte.prbs(7.0), None);
+
+        let ext = Numerology::lte_extended();
+        assert_eq!(ext.symbols_per_subframe(), 12);
+        assert_eq!(ext.cp_len(5, 2048), 512);
+        assert_eq!(ext.subframe_len(2048), 30720);
+
+        for (fr, mu) in &[
+            (FrequencyRange::Fr1, 0),
+            (FrequencyRange::Fr1, 1),
+            (FrequencyRange::Fr1, 2),
+            (FrequencyRange::Fr2, 3),
+        ] {
+            let (nr, mu) = (Numerology::nr(*fr, *mu), *mu);
+            assert_eq!(nr.scs(), 15e3 * (1 << mu) as f32);
+            assert_eq!(nr.symbols_per_slot(), 14);
+            let cp = nr.cp_lengths(4096);
+            assert_eq!(cp.len(), 14 << mu);
+            // the long prefix every 0.5ms
+            assert_eq!(cp[0], 288 + (32 << mu));
+            assert_eq!(cp[7 << mu], cp[0]);
+            assert_eq!(cp.iter().filter(|c| **c == 288).count(), (14 << mu) - 2);
+            assert_eq!(nr.subframe_len(4096), 61440 << mu);
+        }
+        let (fr1, fr2) = (FrequencyRange::Fr1, FrequencyRange::Fr2);
+        assert_eq!(Numerology::nr(fr1, 0).prbs(50.0), Some(270));
+        assert_eq!(Numerology::nr(fr2, 3).prbs(400.0), Some(264));
+        assert_eq!(Numerology::nr(fr2, 3).fft_size(264), 4096);
+        assert_eq!(Numerology::nr(fr2, 4).prbs(100.0), None);
+
+        // 60 kHz: FR1 and FR2 define the same bandwidths with different sizes
+        let nr = Numerology::nr(fr1, 2);
+        assert_eq!(nr.fr(), fr1);
+        assert_eq!(nr.prbs(50.0), Some(65));
+        assert_eq!(nr.prbs(100.0), Some(135));
+        assert_eq!(nr.prbs(200.0), None);
+        let nr = Numerology::nr(fr2, 2);
+        assert_eq!(nr.fr(), fr2);
+        assert_eq!(nr.prbs(50.0), Some(66));
+        assert_eq!(nr.prbs(100.0), Some(132));
+        assert_eq!(nr.prbs(200.0), Some(264));
+        assert_eq!(nr.prbs(10.0), None);
+        assert_ne!(Numerology::nr(fr1, 2), Numerology::nr(fr2, 2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn nr_fr1_120khz() {
+        Numerology::nr(FrequencyRange::Fr1, 3);
+    }
+
+    #[test]
+    fn mapping() {
+        let mut grid = ResourceGrid::new(2, 3);
+        assert_eq!(grid.subcarriers(), 24);
+
+        let values = (0..30)
+            .map(|i| cf32::new(i as f32, 0.0))
+            .collect::<Vec<_>>();
+        // only 24 + 2 elements are selected
+        let mapped = grid.map(&values, |k, l| l == 1 || k < 2);
+        assert_eq!(mapped, 24 + 2 + 2);
+        assert_eq!(grid[(0, 0)], values[0]);
+        assert_eq!(grid[(1, 0)], values[1]);
+        assert_eq!(grid[(0, 1)], values[2]);
+        assert_eq!(grid[(23, 1)], values[25]);
+        assert_eq!(grid[(1, 2)], values[27]);
+        assert_eq!(grid[(2, 2)], cf32::default());
+
+        let mut out = Vec::new();
+        grid.extract(&mut out, ResourceGrid::in_prb(1, 1));
+        assert_eq!(out, values[14..26].to_vec());
+
+        grid.symbol_mut(2)[5] = cf32::new(0.0, 1.0);
+        assert_eq!(grid[(5, 2)], cf32::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn loopback() {
+        for num in &[
+            Numerology::lte(),
+            Numerology::nr(FrequencyRange::Fr1, 1),
+            Numerology::nr_extended(),
+        ] {
+            let mut grid = ResourceGrid::new(6, num.symbols_per_subframe());
+            let values = (0..grid.subcarriers() * grid.symbols())
+                .map(|i| cf32::from_polar(&1.0, &(i as f32 * 0.7)))
+                .collect::<Vec<_>>();
+            grid.map(&values, |_, _| true);
+
+            let samples = grid.to_time(num);
+            assert_eq!(samples.len(), num.subframe_len(128));
+            let rx = ResourceGrid::from_time(&samples, num, 6);
+            assert_eq!(rx.symbols(), grid.symbols());
+            for l in 0..grid.symbols() {
+                assert_evm!(rx.symbol(l), grid.symbol(l), -60.0);
+            }
+        }
+
+        // LTE leaves DC empty, NR uses it
+        let mut grid = ResourceGrid::new(6, 1);
+        grid[(36, 0)] = cf32::new(1.0, 0.0);
+        let lte = grid.to_time(&Numerology::lte());
+        let nr = grid.to_time(&Numerology::nr(FrequencyRange::Fr1, 0));
+        let mut fft = Cfft::with_len(128);
+        assert!(fft.tfwd(&lte[10..], Scale::SN)[1].norm() > 0.9);
+        assert!(fft.tfwd(&nr[10..], Scale::SN)[0].norm() > 0.9);
+    }
+}
@@ -0,0 +1,396 @@
+use crate::cf32;
+use crate::fft::{Cfft, Fft, Scale};
+
+/// LTE/NR numerologies and resource grids
+pub mod grid;
+
+/// Normal cyclic prefix of LTE scaled to the FFT size: one slot of 7 symbols,
+/// the first with ```160/2048 * N``` samples, the others with ```144/2048 * N```
+pub fn normal_cp(fft_size: usize) -> Vec<usize> {
+    let mut cp = vec![144 * fft_size / 2048; 7];
+    cp[0] = 160 * fft_size / 2048;
+    cp
+}
+
+/// Extended cyclic prefix of LTE scaled to the FFT size: a quarter of the FFT size for all symbols
+pub fn extended_cp(fft_size: usize) -> Vec<usize> {
+    vec![fft_size / 4]
+}
+
+/// OFDM modulator and demodulator
+///
+/// The ```used``` subcarriers are placed symmetrically around DC, leaving guard bands at
+/// both edges of the spectrum. By default the DC subcarrier is left empty and the subcarrier
+/// above it is used instead. Subcarriers are numbered ```0..used``` from the lowest to the highest
+/// frequency; pilots occupy some of them, the remaining ones carry data in ascending order.
+///
+/// The cyclic prefix lengths are given per symbol and repeat (e.g. the 7 symbols of an LTE slot),
+/// the position within that cycle is kept across calls.
+/// The IFFT is scaled by ```1/sqrt(N)```, so unit power subcarriers result in
+/// ```used/N``` average power in the time domain.
+///
+/// # Example
+/// ```
+/// use aether_primitives::{cf32, assert_evm};
+/// use aether_primitives::ofdm::{self, Ofdm};
+///
+/// // 802.11a/g-like: 64 subcarriers, 52 used, 4 pilots, 16 samples cyclic prefix
+/// let mut tx = Ofdm::new(64, 52)
+///     .with_cp(vec![16])
+///     .with_pilots(&[5, 19, 32, 46], &[cf32::new(1.0, 0.0); 4]);
+/// let mut rx = tx.clone();
+/// assert_eq!(tx.data_carriers(), 48);
+///
+/// let data = vec![cf32::new(0.0, 1.0); 2 * 48];
+/// let mut samples = Vec::new();
+/// tx.modulate(&data, &mut samples);
+/// assert_eq!(samples.len(), 2 * (64 + 16));
+///
+/// let (mut demod, mut pilots) = (Vec::new(), Vec::new());
+/// rx.demod(&samples, &mut demod, &mut pilots);
+/// assert_evm!(&demod, &data, -60.0);
+/// ```
+pub struct Ofdm {
+    fft_size: usize,
+    /// fft bins of the data subcarriers
+    data: Vec<usize>,
+    /// fft bins and values of the pilots
+    pilots: Vec<(usize, cf32)>,
+    /// fft bins of all used subcarriers
+    used: Vec<usize>,
+    cp: Vec<usize>,
+    /// position within the cyclic prefix cycle
+    symbol: usize,
+    fft: Cfft,
+    tmp: Vec<cf32>,
+}
+
+impl Clone for Ofdm {
+    fn clone(&self) -> Ofdm {
+        Ofdm {
+            fft_size: self.fft_size,
+            data: self.data.clone(),
+            pilots: self.pilots.clone(),
+            used: self.used.clone(),
+            cp: self.cp.clone(),
+            symbol: self.symbol,
+            fft: Cfft::with_len(self.fft_size),
+            tmp: self.tmp.clone(),
+        }
+    }
+}
+
+impl Ofdm {
+    /// Create an OFDM modem with ```used``` subcarriers out of ```fft_size``` and an empty DC
+    /// subcarrier, without cyclic prefix and pilots
+    /// # Panics
+    /// Panics if the subcarriers do not fit into the FFT
+    pub fn new(fft_size: usize, used: usize) -> Ofdm {
+        let mut ofdm = Ofdm {
+            fft_size,
+            data: Vec::new(),
+            pilots: Vec::new(),
+            used: Vec::new(),
+            cp: vec![0],
+            symbol: 0,
+            fft: Cfft::with_len(fft_size),
+            tmp: vec![cf32::default(); fft_size],
+        };
+        ofdm.map(used, true, &[]);
+        ofdm
+    }
+
+    /// Also use the DC subcarrier (the subcarriers then range from ```-used/2``` to ```used/2 - 1```)
+    pub fn with_dc(mut self) -> Ofdm {
+        let pilots = self.pilot_positions();
+        self.map(self.used.len(), false, &pilots);
+        self
+    }
+
+    /// Use the given cyclic prefix lengths (repeating per symbol), e.g. [normal_cp](normal_cp)
+    /// # Panics
+    /// Panics if no lengths are given or a prefix exceeds the FFT size
+    pub fn with_cp(mut self, cp: Vec<usize>) -> Ofdm {
+        assert!(
+            !cp.is_empty() && cp.iter().all(|c| *c <= self.fft_size),
+            "Invalid cyclic prefix lengths"
+        );
+        self.cp = cp;
+        self.symbol = 0;
+        self
+    }
+
+    /// Insert pilots with the given values on the given subcarriers (```0..used```)
+    /// # Panics
+    /// Panics if a subcarrier is out of range or the numbers of positions and values differ
+    pub fn with_pilots(mut self, positions: &[usize], values: &[cf32]) -> Ofdm {
+        assert_eq!(
+            positions.len(),
+            values.len(),
+            "Vectors must have same length"
+        );
+        let dc = self.used.iter().all(|b| *b != 0);
+        self.map(self.used.len(), dc, positions);
+        self.pilots = positions
+            .iter()
+            .zip(values)
+            .map(|(p, v)| (self.used[*p], *v))
+            .collect();
+        self
+    }
+
+    /// map the logical subcarriers to fft bins and split them into data and pilots
+    fn map(&mut self, used: usize, skip_dc: bool, pilots: &[usize]) {
+        let n = self.fft_size;
+        assert!(
+            used + skip_dc as usize <= n,
+            "The used subcarriers must fit into the FFT"
+        );
+        let half = used / 2;
+        self.used = (0..used)
+            .map(|k| {
+                if k < half {
+                    n - half + k
+                } else {
+                    k - half + skip_dc as usize
+                }
+            })
+            .collect();
+        assert!(
+            pilots.iter().all(|p| *p < used),
+            "Pilot subcarrier out of range"
+        );
+        self.data = (0..used)
+            .filter(|k| !pilots.contains(k))
+            .map(|k| self.used[k])
+            .collect();
+        let values = self.pilots.iter().map(|(_, v)| *v).collect::<Vec<_>>();
+        self.pilots = pilots
+            .iter()
+            .zip(values)
+            .map(|(p, v)| (self.used[*p], v))
+            .collect();
+    }
+
+    fn pilot_positions(&self) -> Vec<usize> {
+        self.pilots
+            .iter()
+            .map(|(b, _)| self.used.iter().position(|u| u == b).unwrap())
+            .collect()
+    }
+
+    /// Number of data subcarriers per symbol
+    pub fn data_carriers(&self) -> usize {
+        self.data.len()
+    }
+
+    /// The FFT size
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    /// Number of samples (including the cyclic prefix) of the next symbol
+    pub fn symbol_len(&self) -> usize {
+        self.fft_size + self.cp[self.symbol]
+    }
+
+    /// Restart the cyclic prefix cycle at its first symbol
+    pub fn reset(&mut self) {
+        self.symbol = 0;
+    }
+
+    /// Modulate ```data``` (a multiple of [data_carriers](Ofdm::data_carriers) values)
+    /// onto OFDM symbols, appending the time domain samples to ```output```
+    ///
+    /// If all used subcarriers are pilots (e.g. for a preamble), ```data``` must be empty
+    /// and each call produces a single symbol.
+    /// # Panics
+    /// Panics if the data does not fill a whole number of symbols
+    pub fn modulate(&mut self, data: &[cf32], output: &mut Vec<cf32>) {
+        if self.data.is_empty() {
+            assert!(
+                data.is_empty(),
+                "There are no data subcarriers, the data must be empty"
+            );
+            self.put_symbol(&[], output);
+            return;
+        }
+        assert!(
+            data.len() % self.data.len() == 0,
+            "The data must fill a whole number of OFDM symbols"
+        );
+        for chunk in data.chunks(self.data.len()) {
+            self.put_symbol(chunk, output);
+        }
+    }
+
+    /// map one symbol's data and the pilots to the subcarriers, transform and prepend the cyclic prefix
+    fn put_symbol(&mut self, data: &[cf32], output: &mut Vec<cf32>) {
+        self.tmp.iter_mut().for_each(|x| *x = cf32::default());
+        for (b, d) in self.data.iter().zip(data) {
+            self.tmp[*b] = *d;
+        }
+        for (b, p) in self.pilots.iter() {
+            self.tmp[*b] = *p;
+        }
+        self.fft.ibwd(&mut self.tmp, Scale::SN);
+
+        let cp = self.cp[self.symbol];
+        output.extend_from_slice(&self.tmp[self.fft_size - cp..]);
+        output.extend_from_slice(&self.tmp);
+        self.symbol = (self.symbol + 1) % self.cp.len();
+    }
+
+    /// Demodulate as many whole OFDM symbols from ```samples``` as possible:
+    /// removes the cyclic prefix, transforms each symbol and appends the data subcarriers
+    /// to ```data``` and the received pilots to ```pilots```.
+    /// Returns the number of samples consumed.
+    pub fn demod(
+        &mut self,
+        samples: &[cf32],
+        data: &mut Vec<cf32>,
+        pilots: &mut Vec<cf32>,
+    ) -> usize {
+        let mut pos = 0;
+        while pos + self.symbol_len() <= samples.len() {
+            let start = pos + self.cp[self.symbol];
+            let bins = self
+                .fft
+                .tfwd(&samples[start..start + self.fft_size], Scale::SN);
+            data.extend(self.data.iter().map(|b| bins[*b]));
+            pilots.extend(self.pilots.iter().map(|(b, _)| bins[*b]));
+
+            pos += self.symbol_len();
+            self.symbol = (self.symbol + 1) % self.cp.len();
+        }
+        pos
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::fft::{Cfft, Fft, Scale};
+    use crate::modulation::{qpsk, Modulation};
+    use crate::ofdm::{self, Ofdm};
+    use crate::util::random_bits;
+
+    #[test]
+    fn cp() {
+        assert_eq!(
+            ofdm::normal_cp(2048),
+            vec![160, 144, 144, 144, 144, 144, 144]
+        );
+        assert_eq!(ofdm::normal_cp(128), vec![10, 9, 9, 9, 9, 9, 9]);
+        assert_eq!(ofdm::extended_cp(512), vec![128]);
+    }
+
+    #[test]
+    fn mapping() {
+        let mut m = Ofdm::new(16, 8).with_pilots(&[0, 7], &[cf32::new(2.0, 0.0); 2]);
+        assert_eq!(m.data_carriers(), 6);
+        let data = (1..=6)
+            .map(|i| cf32::new(i as f32, 0.0))
+            .collect::<Vec<_>>();
+        let mut samples = Vec::new();
+        m.modulate(&data, &mut samples);
+
+        let bins = Cfft::with_len(16).tfwd(&samples, Scale::SN).to_vec();
+        let expected = [
+            0.0, 4.0, 5.0, 6.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0, 1.0, 2.0, 3.0,
+        ]
+        .iter()
+        .map(|x| cf32::new(*x, 0.0))
+        .collect::<Vec<_>>();
+        for (b, e) in bins.iter().zip(&expected) {
+            assert!((b - e).norm() < 1e-5, "{:?} != {:?}", bins, expected);
+        }
+
+        // with DC the upper half starts at bin 0
+        let mut m = Ofdm::new(16, 8)
+            .with_pilots(&[0, 7], &[cf32::new(2.0, 0.0); 2])
+            .with_dc();
+        samples.clear();
+        m.modulate(&data, &mut samples);
+        let bins = Cfft::with_len(16).tfwd(&samples, Scale::SN).to_vec();
+        assert!((bins[0] - cf32::new(4.0, 0.0)).norm() < 1e-5);
+        assert!((bins[3] - cf32::new(2.0, 0.0)).norm() < 1e-5);
+        assert!(bins[4].norm() < 1e-5);
+    }
+
+    #[test]
+    fn qpsk_loopback() {
+        let fft_size = 128;
+        let pilots = [
+            cf32::new(1.0, 0.0),
+            cf32::new(-1.0, 0.0),
+            cf32::new(0.0, 1.0),
+        ];
+        let mut tx = Ofdm::new(fft_size, 72)
+            .with_cp(ofdm::normal_cp(fft_size))
+            .with_pilots(&[3, 36, 68], &pilots);
+        let mut rx = tx.clone();
+        let carriers = tx.data_carriers();
+        assert_eq!(carriers, 69);
+
+        // 10 symbols: a slot and a bit
+        let m = qpsk();
+        let bits = random_bits(2 * carriers * 10, 815);
+        let symbols = m.modulate(&bits);
+        let mut samples = Vec::new();
+        for block in symbols.chunks(carriers * 3) {
+            tx.modulate(block, &mut samples);
+        }
+        assert_eq!(samples.len(), 10 * fft_size + (10 + 6 * 9) + (10 + 2 * 9));
+
+        // the cyclic prefix is a copy of the end of the symbol
+        assert_eq!(samples[..10], samples[fft_size..fft_size + 10]);
+
+        // feed the receiver in odd blocks
+        let (mut data, mut rx_pilots) = (Vec::new(), Vec::new());
+        let mut pos = 0;
+        for len in [100, 500, 1000].iter().cycle() {
+            if pos >= samples.len() {
+                break;
+            }
+            let end = (pos + len).min(samples.len());
+            pos += rx.demod(&samples[pos..end], &mut data, &mut rx_pilots);
+        }
+        assert_evm!(&data, &symbols, -60.0);
+        assert_evm!(&rx_pilots, &pilots.repeat(10), -60.0);
+
+        let mut demod = Vec::new();
+        m.demod_naive(&mut data.iter(), &mut demod);
+        assert_eq!(bits, demod);
+    }
+
+    #[test]
+    fn pilots_only() {
+        // a preamble with every subcarrier being a pilot
+        let values = (0..8)
+            .map(|i| cf32::new(if i % 2 == 0 { 1.0 } else { -1.0 }, 0.0))
+            .collect::<Vec<_>>();
+        let mut tx = Ofdm::new(16, 8)
+            .with_cp(vec![4])
+            .with_pilots(&(0..8).collect::<Vec<_>>(), &values);
+        let mut rx = tx.clone();
+        assert_eq!(tx.data_carriers(), 0);
+
+        let mut samples = Vec::new();
+        tx.modulate(&[], &mut samples);
+        tx.modulate(&[], &mut samples);
+        assert_eq!(samples.len(), 2 * (16 + 4));
+
+        let (mut data, mut pilots) = (Vec::new(), Vec::new());
+        assert_eq!(rx.demod(&samples, &mut data, &mut pilots), samples.len());
+        assert!(data.is_empty());
+        assert_evm!(&pilots, &values.repeat(2), -60.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn pilots_only_with_data() {
+        let mut tx = Ofdm::new(16, 2).with_pilots(&[0, 1], &[cf32::new(1.0, 0.0); 2]);
+        tx.modulate(&[cf32::default()], &mut Vec::new());
+    }
+}
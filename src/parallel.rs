@@ -0,0 +1,204 @@
+use crate::aligned::AlignedBuf;
+use crate::cf32;
+use crate::vecops::VecOps;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Default number of elements above which operations are split across the rayon thread pool
+pub const DEFAULT_THRESHOLD: usize = 1 << 16;
+
+/// Number of elements each rayon task works on
+const CHUNK_LEN: usize = 1 << 14;
+
+static THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_THRESHOLD);
+
+/// Set the number of elements from which on [ParVecOps](ParVecOps) operations are run in parallel.
+/// Shorter vectors are processed on the calling thread, since the overhead of
+/// distributing the work would outweigh the gains.
+/// This is a global setting which affects all threads.
+pub fn set_threshold(len: usize) {
+    THRESHOLD.store(len, Ordering::Relaxed);
+}
+
+/// The number of elements from which on [ParVecOps](ParVecOps) operations are run in parallel
+pub fn threshold() -> usize {
+    THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Parallel counterparts of the element-wise [VecOps](crate::vecops::VecOps) operations.
+/// Vectors longer than the [threshold](threshold) are split into chunks
+/// which are processed on the global rayon thread pool.
+/// Each element is computed exactly like its ```vec_*``` counterpart does,
+/// so the results are identical.
+///
+/// # Example
+/// ```
+/// use aether_primitives::{cf32, vecops::VecOps, parallel::{self, ParVecOps}};
+///
+/// parallel::set_threshold(1024);
+/// let mut a = vec![cf32::new(1.0, 2.0); 100_000];
+/// let b = vec![cf32::new(0.0, 1.0); 100_000];
+///
+/// let mut correct = a.clone();
+/// correct.vec_mul(&b).vec_scale(0.5);
+///
+/// a.par_vec_mul(&b).par_vec_scale(0.5);
+/// assert_eq!(a, correct);
+/// ```
+pub trait ParVecOps {
+    /// scale each element by a scalar
+    fn par_vec_scale(&mut self, scale: f32) -> &mut Self;
+
+    /// element-wise multiply this vector with the other one
+    fn par_vec_mul(&mut self, other: impl AsRef<[cf32]>) -> &mut Self;
+
+    /// element-wise divide this vector by the other one
+    fn par_vec_div(&mut self, other: impl AsRef<[cf32]>) -> &mut Self;
+
+    /// element-wise add the other vector to this one
+    fn par_vec_add(&mut self, other: impl AsRef<[cf32]>) -> &mut Self;
+
+    /// element-wise subtract the other vector from this one
+    fn par_vec_sub(&mut self, other: impl AsRef<[cf32]>) -> &mut Self;
+
+    /// complex conjugate each element
+    fn par_vec_conj(&mut self) -> &mut Self;
+
+    /// apply a function to each element
+    /// The function may be called from several threads at once.
+    fn par_vec_mutate(&mut self, f: impl Fn(&mut cf32) + Sync + Send) -> &mut Self;
+}
+
+/// apply ```op``` to matching chunks of ```a``` and ```b```, in parallel if they are long enough
+#[inline]
+fn zip_chunks(a: &mut [cf32], b: &[cf32], op: impl Fn(&mut [cf32], &[cf32]) + Sync + Send) {
+    assert_eq!(a.len(), b.len(), "Vectors must have same length");
+    if a.len() < threshold() {
+        op(a, b);
+    } else {
+        a.par_chunks_mut(CHUNK_LEN)
+            .zip(b.par_chunks(CHUNK_LEN))
+            .for_each(|(a, b)| op(a, b));
+    }
+}
+
+/// apply ```op``` to chunks of ```a```, in parallel if it is long enough
+#[inline]
+fn chunks(a: &mut [cf32], op: impl Fn(&mut [cf32]) + Sync + Send) {
+    if a.len() < threshold() {
+        op(a);
+    } else {
+        a.par_chunks_mut(CHUNK_LEN).for_each(op);
+    }
+}
+
+macro_rules! impl_par_vec_ops {
+    ($type:ty) => {
+        impl ParVecOps for $type {
+            fn par_vec_scale(&mut self, scale: f32) -> &mut Self {
+                chunks(&mut self[..], |a| {
+                    a.vec_scale(scale);
+                });
+                self
+            }
+
+            fn par_vec_mul(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+                zip_chunks(&mut self[..], other.as_ref(), |a, b| {
+                    a.vec_mul(b);
+                });
+                self
+            }
+
+            fn par_vec_div(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+                zip_chunks(&mut self[..], other.as_ref(), |a, b| {
+                    a.vec_div(b);
+                });
+                self
+            }
+
+            fn par_vec_add(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+                zip_chunks(&mut self[..], other.as_ref(), |a, b| {
+                    a.vec_add(b);
+                });
+                self
+            }
+
+            fn par_vec_sub(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+                zip_chunks(&mut self[..], other.as_ref(), |a, b| {
+                    a.vec_sub(b);
+                });
+                self
+            }
+
+            fn par_vec_conj(&mut self) -> &mut Self {
+                chunks(&mut self[..], |a| {
+                    a.vec_conj();
+                });
+                self
+            }
+
+            fn par_vec_mutate(&mut self, f: impl Fn(&mut cf32) + Sync + Send) -> &mut Self {
+                chunks(&mut self[..], |a| a.iter_mut().for_each(&f));
+                self
+            }
+        }
+    };
+}
+
+impl_par_vec_ops!([cf32]);
+impl_par_vec_ops!(Vec<cf32>);
+impl_par_vec_ops!(AlignedBuf<cf32>);
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::parallel::{ParVecOps, CHUNK_LEN};
+    use crate::vecops::VecOps;
+
+    fn make_vecs(len: usize) -> (Vec<cf32>, Vec<cf32>) {
+        let a = (0..len)
+            .map(|i| cf32::new(i as f32 * 0.5, 1.0 - i as f32))
+            .collect::<Vec<_>>();
+        let b = (0..len)
+            .map(|i| cf32::new((i % 7) as f32 + 1.0, 0.25 * (i % 13) as f32))
+            .collect::<Vec<_>>();
+        (a, b)
+    }
+
+    #[test]
+    fn identical_results() {
+        // the default threshold is used, so these cover the sequential and the parallel path
+        // as well as a partial last chunk
+        for len in &[100, 3 * CHUNK_LEN + 17, super::DEFAULT_THRESHOLD * 2 + 5] {
+            let (a, b) = make_vecs(*len);
+
+            let mut seq = a.clone();
+            seq.vec_mul(&b)
+                .vec_add(&b)
+                .vec_scale(0.3)
+                .vec_div(&b)
+                .vec_sub(&a)
+                .vec_conj()
+                .vec_mutate(|c| c.re = -c.re);
+
+            let mut par = a.clone();
+            par.par_vec_mul(&b)
+                .par_vec_add(&b)
+                .par_vec_scale(0.3)
+                .par_vec_div(&b)
+                .par_vec_sub(&a)
+                .par_vec_conj()
+                .par_vec_mutate(|c| c.re = -c.re);
+
+            assert_eq!(seq, par);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn length_mismatch() {
+        let (mut a, _) = make_vecs(10);
+        let (b, _) = make_vecs(11);
+        a.par_vec_mul(&b);
+    }
+}
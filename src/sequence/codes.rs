@@ -0,0 +1,269 @@
+use crate::cf32;
+use std::f32::consts::PI;
+
+/// Greatest common divisor
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Zadoff-Chu sequence ```x(n) = exp(-j pi u m (m + c) / N)``` with ```m = (n + shift) mod N```
+/// and ```c = N mod 2``` for the root ```u``` (3GPP TS 36.211 5.5.1.1, TS 38.211 6.3.3.1).
+///
+/// The sequence has a constant amplitude, its periodic autocorrelation is zero for all
+/// non-zero lags and the cross-correlation of two roots of a prime length is ```sqrt(N)```.
+///
+/// # Example
+/// ```
+/// use aether_primitives::sequence;
+///
+/// // root 25 of the LTE PSS (length 63 with the DC removed later)
+/// let zc = sequence::zadoff_chu(25, 63, 0);
+/// assert_eq!(zc.len(), 63);
+/// assert!(zc.iter().all(|x| (x.norm() - 1.0).abs() < 1e-6));
+///
+/// // a cyclic shift rotates the sequence
+/// let shifted = sequence::zadoff_chu(25, 63, 5);
+/// assert!((shifted[0] - zc[5]).norm() < 1e-6);
+/// ```
+/// # Panics
+/// Panics if ```root``` is not in ```1..len``` or not coprime to ```len```
+pub fn zadoff_chu(root: usize, len: usize, shift: usize) -> Vec<cf32> {
+    assert!(
+        root > 0 && root < len && gcd(root, len) == 1,
+        "The root must be in 1..len and coprime to len"
+    );
+    let c = len % 2;
+    // the phase is evaluated modulo 2N in integers to stay exact for long sequences
+    let period = 2 * len as u64;
+    (0..len)
+        .map(|n| {
+            let m = ((n + shift) % len) as u64;
+            let k = (root as u64 % period) * (m * (m + c as u64) % period) % period;
+            cf32::from_polar(&1.0, &(-PI * k as f32 / len as f32))
+        })
+        .collect()
+}
+
+/// The known Barker codes as ```+1```/```-1``` chips (lengths 2, 3, 4, 5, 7, 11 and 13)
+/// with aperiodic autocorrelation sidelobes of at most 1.
+///
+/// # Example
+/// ```
+/// use aether_primitives::sequence;
+///
+/// assert_eq!(sequence::barker(7), Some(&[1i8, 1, 1, -1, -1, 1, -1][..]));
+/// assert_eq!(sequence::barker(6), None);
+/// ```
+pub fn barker(len: usize) -> Option<&'static [i8]> {
+    match len {
+        2 => Some(&[1, -1]),
+        3 => Some(&[1, 1, -1]),
+        4 => Some(&[1, 1, -1, 1]),
+        5 => Some(&[1, 1, 1, -1, 1]),
+        7 => Some(&[1, 1, 1, -1, -1, 1, -1]),
+        11 => Some(&[1, 1, 1, -1, -1, -1, 1, -1, -1, 1, -1]),
+        13 => Some(&[1, 1, 1, 1, 1, -1, -1, 1, 1, -1, 1, -1, 1]),
+        _ => None,
+    }
+}
+
+/// Row ```index``` of the Sylvester Hadamard matrix of size ```len```
+/// (Walsh code in natural order), ```w(n) = (-1)^popcount(index & n)```
+///
+/// # Example
+/// ```
+/// use aether_primitives::sequence;
+///
+/// assert_eq!(sequence::walsh(3, 4), vec![1, -1, -1, 1]);
+/// ```
+/// # Panics
+/// Panics if ```len``` is not a power of two or ```index``` is out of range
+pub fn walsh(index: usize, len: usize) -> Vec<i8> {
+    assert!(len.is_power_of_two(), "The length must be a power of two");
+    assert!(index < len, "The index must be smaller than the length");
+    (0..len)
+        .map(|n| {
+            if (index & n).count_ones() % 2 == 0 {
+                1
+            } else {
+                -1
+            }
+        })
+        .collect()
+}
+
+/// Orthogonal variable spreading factor code ```C_sf,k``` (3GPP TS 25.213 4.3.1)
+///
+/// Built from the code tree ```C_2n,2k = (C_n,k, C_n,k)``` and ```C_2n,2k+1 = (C_n,k, -C_n,k)```,
+/// which is the Walsh code with the bit-reversed index.
+///
+/// # Example
+/// ```
+/// use aether_primitives::sequence;
+///
+/// assert_eq!(sequence::ovsf(4, 1), vec![1, 1, -1, -1]);
+/// assert_eq!(sequence::ovsf(4, 2), vec![1, -1, 1, -1]);
+/// ```
+/// # Panics
+/// Panics if ```sf``` is not a power of two or ```k``` is out of range
+pub fn ovsf(sf: usize, k: usize) -> Vec<i8> {
+    assert!(
+        sf.is_power_of_two(),
+        "The spreading factor must be a power of two"
+    );
+    assert!(
+        k < sf,
+        "The code index must be smaller than the spreading factor"
+    );
+    let bits = sf.trailing_zeros();
+    let index = if bits == 0 {
+        0
+    } else {
+        k.reverse_bits() >> (usize::BITS - bits)
+    };
+    walsh(index, sf)
+}
+
+/// Golay complementary pair of length ```2^m``` built recursively from
+/// ```a' = (a, b)``` and ```b' = (a, -b)```, starting with ```a = b = (1)```.
+///
+/// The sum of the aperiodic autocorrelations of ```a``` and ```b``` is zero for all non-zero lags.
+///
+/// # Example
+/// ```
+/// use aether_primitives::sequence;
+///
+/// let (a, b) = sequence::golay(2);
+/// assert_eq!(a, vec![1, 1, 1, -1]);
+/// assert_eq!(b, vec![1, 1, -1, 1]);
+/// ```
+pub fn golay(m: u32) -> (Vec<i8>, Vec<i8>) {
+    (0..m).fold((vec![1], vec![1]), |(a, b), _| {
+        let mut next_a = a.clone();
+        next_a.extend_from_slice(&b);
+        let mut next_b = a;
+        next_b.extend(b.iter().map(|x| -x));
+        (next_a, next_b)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::sequence::{barker, golay, ovsf, walsh, zadoff_chu};
+
+    /// correlation of ```a``` and ```b``` for lags ```0..N```, computed in the frequency domain
+    /// (zero-padded to twice the length for aperiodic correlation)
+    #[cfg(feature = "fft")]
+    fn correlate(a: &[cf32], b: &[cf32], aperiodic: bool) -> Vec<cf32> {
+        use crate::fft::Scale;
+        use crate::vecops::VecOps;
+
+        let len = if aperiodic { 2 * a.len() } else { a.len() };
+        let pad = |x: &[cf32]| {
+            let mut v = x.to_vec();
+            v.resize(len, cf32::default());
+            v
+        };
+        let (mut fa, mut fb) = (pad(a), pad(b));
+        fa.vec_fft(Scale::None);
+        fb.vec_fft(Scale::None);
+        // sum_n b(n + k) conj(a(n))
+        fb.vec_mul_conj(&fa).vec_ifft(Scale::N);
+        fb.truncate(a.len());
+        fb
+    }
+
+    fn bipolar(x: &[i8]) -> Vec<cf32> {
+        x.iter().map(|c| cf32::new(f32::from(*c), 0.0)).collect()
+    }
+
+    #[test]
+    #[cfg(feature = "fft")]
+    fn zadoff_chu_correlation() {
+        for len in &[63usize, 139, 839] {
+            let zc = zadoff_chu(1, *len, 0);
+            let auto = correlate(&zc, &zc, false);
+            assert!((auto[0].norm() - *len as f32).abs() < 1e-2);
+            assert!(auto[1..].iter().all(|x| x.norm() < 1e-2));
+
+            // the cyclic shift moves the correlation peak
+            let shifted = zadoff_chu(1, *len, 7);
+            let peak = correlate(&shifted, &zc, false);
+            assert!((peak[7].norm() - *len as f32).abs() < 1e-2);
+        }
+
+        // prime length: constant cross-correlation sqrt(N)
+        let (a, b) = (zadoff_chu(29, 839, 0), zadoff_chu(34, 839, 0));
+        let cross = correlate(&a, &b, false);
+        assert!(cross
+            .iter()
+            .all(|x| (x.norm() - (839f32).sqrt()).abs() < 1e-2));
+    }
+
+    #[test]
+    #[cfg(feature = "fft")]
+    fn barker_sidelobes() {
+        for len in &[2, 3, 4, 5, 7, 11, 13] {
+            let code = bipolar(barker(*len).unwrap());
+            let auto = correlate(&code, &code, true);
+            assert!((auto[0].re - *len as f32).abs() < 1e-4);
+            assert!(auto[1..].iter().all(|x| x.norm() <= 1.0 + 1e-4));
+        }
+        assert!(barker(1).is_none() && barker(17).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "fft")]
+    fn golay_complementary() {
+        for m in 0..8 {
+            let (a, b) = golay(m);
+            let (a, b) = (bipolar(&a), bipolar(&b));
+            let len = a.len() as f32;
+            let sum = correlate(&a, &a, true)
+                .iter()
+                .zip(correlate(&b, &b, true).iter())
+                .map(|(x, y)| x + y)
+                .collect::<Vec<_>>();
+            assert!((sum[0].re - 2.0 * len).abs() < 1e-3);
+            assert!(sum[1..].iter().all(|x| x.norm() < 1e-3));
+        }
+    }
+
+    #[test]
+    fn orthogonal() {
+        let dot = |a: &[i8], b: &[i8]| a.iter().zip(b).map(|(x, y)| i32::from(x * y)).sum::<i32>();
+        for sf in &[1usize, 2, 8, 64] {
+            let codes = (0..*sf).map(|k| ovsf(*sf, k)).collect::<Vec<_>>();
+            for (i, a) in codes.iter().enumerate() {
+                for (j, b) in codes.iter().enumerate() {
+                    let expected = if i == j { *sf as i32 } else { 0 };
+                    assert_eq!(dot(a, b), expected);
+                }
+            }
+        }
+
+        // the same set as the walsh codes
+        let mut walsh16 = (0..16).map(|i| walsh(i, 16)).collect::<Vec<_>>();
+        let mut ovsf16 = (0..16).map(|k| ovsf(16, k)).collect::<Vec<_>>();
+        walsh16.sort();
+        ovsf16.sort();
+        assert_eq!(walsh16, ovsf16);
+
+        // code tree: the child starts with its parent followed by +-parent
+        for k in 0..16 {
+            let child = ovsf(32, k);
+            let parent = ovsf(16, k / 2);
+            assert_eq!(child[..16], parent[..]);
+            let sign = if k % 2 == 0 { 1 } else { -1 };
+            assert!(child[16..]
+                .iter()
+                .zip(parent.iter())
+                .all(|(c, p)| *c == sign * p));
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use super::{expand, Lfsr};
+
+/// Length of the initial sequence which is discarded by the 3GPP pseudo-random sequence
+pub const NC: u64 = 1600;
+
+/// Pseudo-random (Gold) sequence ```c(n)``` of LTE and NR (3GPP TS 36.211 7.2, TS 38.211 5.2.1)
+///
+/// ```c(n) = (x1(n + Nc) + x2(n + Nc)) mod 2``` with ```Nc = 1600```
+/// - ```x1(n + 31) = (x1(n + 3) + x1(n)) mod 2``` initialised with ```x1(0) = 1, x1(1..31) = 0```
+/// - ```x2(n + 31) = (x2(n + 3) + x2(n + 2) + x2(n + 1) + x2(n)) mod 2```
+///   initialised with the bits of ```c_init```
+///
+/// Instead of generating and discarding the first 1600 bits, both registers are jumped ahead.
+///
+/// # Example
+/// ```
+/// use aether_primitives::sequence::Gold3gpp;
+///
+/// // the scrambling sequence for c_init = 0x1234
+/// let c = Gold3gpp::new(0x1234).take(100).collect::<Vec<u8>>();
+/// assert_eq!(c.len(), 100);
+/// assert_eq!(c, Gold3gpp::sequence(0x1234, 100));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Gold3gpp {
+    x1: Lfsr,
+    x2: Lfsr,
+}
+
+impl Gold3gpp {
+    /// Create a generator for ```c_init``` (only the lower 31 bits are used)
+    pub fn new(c_init: u32) -> Gold3gpp {
+        let mut x1 = Lfsr::from_exponents(&[31, 3, 0], &expand(1, 31)).galois();
+        let mut x2 =
+            Lfsr::from_exponents(&[31, 3, 2, 1, 0], &expand(u64::from(c_init), 31)).galois();
+        x1.jump(NC);
+        x2.jump(NC);
+        Gold3gpp { x1, x2 }
+    }
+
+    /// The first ```len``` bits of ```c(n)``` for ```c_init```
+    pub fn sequence(c_init: u32, len: usize) -> Vec<u8> {
+        Gold3gpp::new(c_init).take(len).collect()
+    }
+}
+
+impl Iterator for Gold3gpp {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        Some(self.x1.next_bit() ^ self.x2.next_bit())
+    }
+}
+
+/// Length of a GPS C/A code
+pub const CA_LEN: usize = 1023;
+
+/// G2 phase selector taps (stages 1..=10) of the GPS C/A codes for PRN 1..=37 (IS-GPS-200 Table 3-Ia)
+const CA_TAPS: [(usize, usize); 37] = [
+    (2, 6),
+    (3, 7),
+    (4, 8),
+    (5, 9),
+    (1, 9),
+    (2, 10),
+    (1, 8),
+    (2, 9),
+    (3, 10),
+    (2, 3),
+    (3, 4),
+    (5, 6),
+    (6, 7),
+    (7, 8),
+    (8, 9),
+    (9, 10),
+    (1, 4),
+    (2, 5),
+    (3, 6),
+    (4, 7),
+    (5, 8),
+    (6, 9),
+    (1, 3),
+    (4, 6),
+    (5, 7),
+    (6, 8),
+    (7, 9),
+    (8, 10),
+    (1, 6),
+    (2, 7),
+    (3, 8),
+    (4, 9),
+    (5, 10),
+    (4, 10),
+    (1, 7),
+    (2, 8),
+    (4, 10),
+];
+
+/// GPS L1 C/A code (1023 chips of ```0``` or ```1```) of the satellite ```prn```
+///
+/// G1 (```1 + x^3 + x^10```) and G2 (```1 + x^2 + x^3 + x^6 + x^8 + x^9 + x^10```) start with all ones,
+/// the code is G1 XOR the sum of two G2 stages selected by the PRN.
+/// PRNs 1 to 32 are used by the satellites, 33 to 37 are reserved for other uses (e.g. ground transmitters).
+///
+/// # Example
+/// ```
+/// use aether_primitives::sequence;
+///
+/// let ca = sequence::gps_ca(1);
+/// assert_eq!(ca.len(), 1023);
+/// // the first 10 chips of PRN 1 are 1440 (octal)
+/// assert_eq!(ca[..10], [1, 1, 0, 0, 1, 0, 0, 0, 0, 0]);
+/// ```
+/// # Panics
+/// Panics if ```prn``` is not in ```1..=37```
+pub fn gps_ca(prn: usize) -> Vec<u8> {
+    assert!(
+        (1..=37).contains(&prn),
+        "GPS C/A codes exist for PRN 1..=37"
+    );
+    let (a, b) = CA_TAPS[prn - 1];
+    // stage i + 1 in bit i, shifting towards stage 10
+    let (mut g1, mut g2) = (0x3FFu16, 0x3FFu16);
+    let stage = |r: u16, s: usize| (r >> (s - 1) & 1) as u8;
+
+    (0..CA_LEN)
+        .map(|_| {
+            let chip = stage(g1, 10) ^ stage(g2, a) ^ stage(g2, b);
+            let f1 = stage(g1, 3) ^ stage(g1, 10);
+            let f2 = [2, 3, 6, 8, 9, 10].iter().fold(0, |f, s| f ^ stage(g2, *s));
+            g1 = (g1 << 1 | u16::from(f1)) & 0x3FF;
+            g2 = (g2 << 1 | u16::from(f2)) & 0x3FF;
+            chip
+        })
+        .collect()
+}
+
+/// Small and large sets of Kasami sequences of period ```N = 2^n - 1``` derived from an
+/// m-sequence ```u``` (given by a primitive polynomial of even degree ```n```).
+///
+/// - ```w```: ```u``` decimated by ```2^(n/2) + 1``` (period ```2^(n/2) - 1```)
+/// - ```v```: ```u``` decimated by ```2^(n/2 + 1) + 1``` (only for the large set)
+///
+/// The small set holds the ```2^(n/2)``` sequences ```u``` and ```u + T^k w```
+/// (```T^k``` is a cyclic shift by ```k```) with a maximum periodic cross-correlation of
+/// ```2^(n/2) + 1```. The large set for ```n = 2 mod 4``` additionally contains the Gold sequences
+/// of ```u``` and ```v``` and their sums with ```T^k w```, ```2^(n/2) * (2^n + 1)``` sequences
+/// with a maximum cross-correlation of ```2^(n/2 + 1) + 1```.
+///
+/// # Example
+/// ```
+/// use aether_primitives::sequence::Kasami;
+///
+/// // x^8 + x^4 + x^3 + x^2 + 1
+/// let k = Kasami::small(&[8, 4, 3, 2, 0]);
+/// assert_eq!(k.len(), 16);
+/// assert_eq!(k.period(), 255);
+/// assert_eq!(k.sequence(3).len(), 255);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Kasami {
+    u: Vec<u8>,
+    w: Vec<u8>,
+    v: Option<Vec<u8>>,
+}
+
+impl Kasami {
+    /// The small set for the primitive polynomial given by its exponents (see [Lfsr](Lfsr))
+    /// # Panics
+    /// Panics if the degree is odd
+    pub fn small(exponents: &[usize]) -> Kasami {
+        let (u, n) = m_sequence(exponents);
+        assert!(n % 2 == 0, "Kasami sets need an even degree");
+        let w = decimate(&u, (1 << (n / 2)) + 1, (1 << (n / 2)) - 1);
+        Kasami { u, w, v: None }
+    }
+
+    /// The large set for the primitive polynomial given by its exponents (see [Lfsr](Lfsr))
+    /// # Panics
+    /// Panics if the degree is not ```2 mod 4```
+    pub fn large(exponents: &[usize]) -> Kasami {
+        let (u, n) = m_sequence(exponents);
+        assert!(n % 4 == 2, "Large Kasami sets need a degree of 2 mod 4");
+        let mut k = Kasami::small(exponents);
+        k.v = Some(decimate(&u, (1 << (n / 2 + 1)) + 1, u.len()));
+        k
+    }
+
+    /// Number of sequences in the set
+    pub fn len(&self) -> usize {
+        let w = self.w.len() + 1;
+        match self.v {
+            None => w,
+            Some(ref v) => (v.len() + 2) * w,
+        }
+    }
+
+    /// Whether the set is empty (never)
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Period (and length) of the sequences
+    pub fn period(&self) -> usize {
+        self.u.len()
+    }
+
+    /// Sequence ```i``` (```0..len()```) of the set
+    ///
+    /// With ```M = 2^(n/2) - 1``` shifts of ```w``` and ```N``` shifts of ```v```, sequence
+    /// ```i = a * (M + 1) + b``` is built from
+    /// - ```a = 0```: ```u```, ```a = 1..=N```: ```u + T^(a-1) v```, ```a = N + 1```: ```v```
+    /// - ```b = 0```: nothing added, ```b = 1..=M```: ```+ T^(b-1) w```
+    ///
+    /// # Panics
+    /// Panics if ```i``` is out of range
+    pub fn sequence(&self, i: usize) -> Vec<u8> {
+        assert!(i < self.len(), "Sequence index out of range");
+        let len = self.u.len();
+        let m = self.w.len() + 1;
+        let (a, b) = (i / m, i % m);
+
+        let mut seq = match (&self.v, a) {
+            (_, 0) => self.u.clone(),
+            (Some(v), a) if a <= len => {
+                (0..len).map(|n| self.u[n] ^ v[(n + a - 1) % len]).collect()
+            }
+            (Some(v), _) => v.clone(),
+            (None, _) => unreachable!(),
+        };
+        if b > 0 {
+            let w = &self.w;
+            seq.iter_mut()
+                .enumerate()
+                .for_each(|(n, s)| *s ^= w[(n + b - 1) % w.len()]);
+        }
+        seq
+    }
+}
+
+/// one period of the m-sequence of the polynomial and its degree
+fn m_sequence(exponents: &[usize]) -> (Vec<u8>, usize) {
+    let n = exponents.iter().copied().max().unwrap_or(0);
+    let lfsr = Lfsr::from_exponents(exponents, &expand(1, n));
+    (lfsr.take((1 << n) - 1).collect(), n)
+}
+
+/// ```period``` values of ```u(q * n)```
+fn decimate(u: &[u8], q: usize, period: usize) -> Vec<u8> {
+    (0..period).map(|n| u[n * q % u.len()]).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sequence::{self, gps_ca, Gold3gpp, Kasami, Lfsr};
+
+    /// periodic correlation of two binary sequences mapped to +-1
+    fn correlation(a: &[u8], b: &[u8], shift: usize) -> i32 {
+        (0..a.len())
+            .map(|n| {
+                if a[n] == b[(n + shift) % b.len()] {
+                    1
+                } else {
+                    -1
+                }
+            })
+            .sum()
+    }
+
+    #[test]
+    fn gold_3gpp() {
+        // independent implementation following the doc example of generate
+        for c_init in &[0u32, 1, 0x1234, 0x7FFF_FFFF, 0x5A5A_5A5A] {
+            let len = 1600 + 2000;
+            let gen1 = |n: usize, s: &[u8]| (s[n - 28] + s[n - 31]) % 2;
+            let gen2 = |n: usize, s: &[u8]| (s[n - 28] + s[n - 29] + s[n - 30] + s[n - 31]) % 2;
+            let x1 = sequence::generate(sequence::expand(1, 31), gen1, len);
+            let x2 = sequence::generate(sequence::expand(u64::from(*c_init), 31), gen2, len);
+            let c = (0..2000)
+                .map(|n| x1[n + 1600] ^ x2[n + 1600])
+                .collect::<Vec<_>>();
+            assert_eq!(Gold3gpp::sequence(*c_init, 2000), c);
+        }
+    }
+
+    #[test]
+    fn gps() {
+        // first 10 chips in octal (IS-GPS-200 Table 3-Ia)
+        let first = [
+            0o1440, 0o1620, 0o1710, 0o1744, 0o1133, 0o1455, 0o1131, 0o1454, 0o1626, 0o1504, 0o1642,
+            0o1750, 0o1764, 0o1772, 0o1775, 0o1776, 0o1156, 0o1467, 0o1633, 0o1715, 0o1746, 0o1763,
+            0o1063, 0o1706, 0o1743, 0o1761, 0o1770, 0o1774, 0o1127, 0o1453, 0o1625, 0o1712, 0o1745,
+            0o1713, 0o1134, 0o1456, 0o1713,
+        ];
+        let codes = (1..=37).map(gps_ca).collect::<Vec<_>>();
+        for (prn, (code, octal)) in codes.iter().zip(first.iter()).enumerate() {
+            let chips = (0..10).fold(0u32, |acc, i| acc << 1 | u32::from(code[i]));
+            assert_eq!(chips, *octal, "PRN {}", prn + 1);
+            // balanced gold codes: 512 ones
+            assert_eq!(code.iter().filter(|c| **c == 1).count(), 512);
+        }
+
+        // three-valued correlation of gold codes for n = 10: -1, -65, 63
+        for a in codes.iter().take(8) {
+            for shift in 1..1023 {
+                assert!([-1, -65, 63].contains(&correlation(a, a, shift)));
+            }
+            for b in codes[..32].iter().filter(|b| *b != a) {
+                for shift in (0..1023).step_by(7) {
+                    assert!([-1, -65, 63].contains(&correlation(a, b, shift)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn kasami_small() {
+        let k = Kasami::small(&[8, 4, 3, 2, 0]);
+        assert_eq!(k.len(), 16);
+        let set = (0..k.len()).map(|i| k.sequence(i)).collect::<Vec<_>>();
+        let u = Lfsr::from_exponents(&[8, 4, 3, 2, 0], &sequence::expand(1, 8))
+            .take(255)
+            .collect::<Vec<_>>();
+        assert_eq!(set[0], u);
+
+        for (i, a) in set.iter().enumerate() {
+            for b in set[i..].iter() {
+                for shift in 0..255 {
+                    let c = correlation(a, b, shift);
+                    if a == b && shift == 0 {
+                        assert_eq!(c, 255);
+                    } else {
+                        assert!(c.abs() <= 17, "{}", c);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn kasami_large() {
+        // x^6 + x + 1
+        let k = Kasami::large(&[6, 1, 0]);
+        assert_eq!(k.len(), 8 * 65);
+        assert_eq!(k.period(), 63);
+        let set = (0..k.len()).map(|i| k.sequence(i)).collect::<Vec<_>>();
+
+        // all distinct, even when shifted
+        for (i, a) in set.iter().enumerate().step_by(13) {
+            for b in set.iter().skip(i) {
+                for shift in 0..63 {
+                    let c = correlation(a, b, shift);
+                    if !(a == b && shift == 0) {
+                        assert!(c.abs() <= 17, "{}", c);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use crate::util::low_bits;
+
+/// Structure of an [Lfsr](Lfsr)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LfsrForm {
+    /// The register holds the next ```degree``` output bits, the feedback is the parity of the taps
+    Fibonacci,
+    /// The output bit is XORed into the taps of the register (one XOR per step instead of a parity)
+    Galois,
+}
+
+/// A binary linear-feedback shift register of up to 64 bits generating the sequence
+/// ```x(n + L) = sum_e x(n + e) mod 2``` for the exponents ```e``` of the polynomial
+/// ```x^L + ... + x^e + ... + 1```.
+///
+/// The register is seeded with the first ```L``` bits ```x(0)..x(L-1)```
+/// (e.g. from [expand](crate::sequence::expand)), so it produces the same sequence as
+/// [generate](crate::sequence::generate) with the corresponding generator,
+/// in either [form](LfsrForm).
+///
+/// # Example
+/// ```
+/// use aether_primitives::sequence::{self, Lfsr};
+///
+/// // the first m-sequence of the LTE pseudo-random sequence (3GPP TS 36.211 7.2):
+/// // x1(n+31) = (x1(n+3) + x1(n)) mod 2
+/// let init = sequence::expand(1, 31);
+/// let mut lfsr = Lfsr::from_exponents(&[31, 3, 0], &init);
+///
+/// let gen = |n: usize, seq: &[u8]| (seq[n - 28] + seq[n - 31]) % 2;
+/// let seq = sequence::generate(init, gen, 1600);
+/// assert_eq!(lfsr.by_ref().take(1600).collect::<Vec<_>>(), seq);
+///
+/// // the same register in Galois form, jumped ahead
+/// let mut galois = Lfsr::from_exponents(&[31, 3, 0], &sequence::expand(1, 31)).galois();
+/// galois.jump(1600);
+/// assert_eq!(galois.next_bits(32), lfsr.next_bits(32));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lfsr {
+    degree: usize,
+    /// bit e set for each exponent e < degree
+    taps: u64,
+    /// feedback mask of the galois form: the taps reversed
+    mask: u64,
+    form: LfsrForm,
+    state: u64,
+}
+
+impl Lfsr {
+    /// Create a register in Fibonacci form for the polynomial given by its exponents
+    /// (in any order, the highest one is the degree, e.g. ```[31, 3, 0]```)
+    /// seeded with ```x(0)..x(L-1)```
+    /// # Panics
+    /// Panics if the degree is not in ```1..=64``` or the seed does not have ```degree``` bits
+    pub fn from_exponents(exponents: &[usize], init: &[u8]) -> Lfsr {
+        let degree = exponents.iter().copied().max().unwrap_or(0);
+        let taps = exponents
+            .iter()
+            .filter(|e| **e < degree)
+            .fold(0u64, |acc, e| acc | 1 << e);
+        Lfsr::from_mask(degree, taps, init)
+    }
+
+    /// Create a register in Fibonacci form of the given degree, where bit ```e``` of ```taps```
+    /// (```e < degree```) is set for each term ```x^e``` of the polynomial
+    /// (e.g. ```degree = 31```, ```taps = 0b1001``` for ```x^31 + x^3 + 1```)
+    /// seeded with ```x(0)..x(L-1)```
+    /// # Panics
+    /// Panics if the degree is not in ```1..=64``` or the seed does not have ```degree``` bits
+    pub fn from_mask(degree: usize, taps: u64, init: &[u8]) -> Lfsr {
+        assert!((1..=64).contains(&degree), "The degree must be in 1..=64");
+        assert_eq!(init.len(), degree, "The seed must have one bit per stage");
+        let state = init
+            .iter()
+            .enumerate()
+            .fold(0u64, |acc, (i, b)| acc | u64::from(b & 1) << i);
+        let taps = taps & low_bits(degree);
+        let mask = (0..degree)
+            .filter(|e| taps >> e & 1 == 1)
+            .fold(0u64, |acc, e| acc | 1 << (degree - 1 - e));
+        Lfsr {
+            degree,
+            taps,
+            mask,
+            form: LfsrForm::Fibonacci,
+            state,
+        }
+    }
+
+    /// Convert to Galois form, continuing the same output sequence
+    pub fn galois(self) -> Lfsr {
+        if self.form == LfsrForm::Galois {
+            return self;
+        }
+        let (window, mask) = (self.state, self.mask);
+        // g_k = x(k) ^ sum_{j<k} x(j) * M_{k-1-j}
+        let state = (0..self.degree).fold(0u64, |acc, k| {
+            let g = (0..k).fold(window >> k & 1, |g, j| {
+                g ^ (window >> j & mask >> (k - 1 - j) & 1)
+            });
+            acc | g << k
+        });
+        Lfsr {
+            form: LfsrForm::Galois,
+            state,
+            ..self
+        }
+    }
+
+    /// Convert to Fibonacci form, continuing the same output sequence
+    pub fn fibonacci(self) -> Lfsr {
+        if self.form == LfsrForm::Fibonacci {
+            return self;
+        }
+        // the next outputs are the fibonacci state
+        let mut tmp = self.clone();
+        let state = tmp.next_bits(self.degree);
+        Lfsr {
+            form: LfsrForm::Fibonacci,
+            state,
+            ..self
+        }
+    }
+
+    /// The structure of this register
+    pub fn form(&self) -> LfsrForm {
+        self.form
+    }
+
+    /// The degree (number of stages)
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// The current register contents (for the Fibonacci form bit ```i``` is the ```i```th next output)
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Produce the next bit (```0``` or ```1```)
+    #[inline]
+    pub fn next_bit(&mut self) -> u8 {
+        let out = self.state & 1;
+        self.state = match self.form {
+            LfsrForm::Fibonacci => {
+                let fb = u64::from((self.state & self.taps).count_ones() & 1);
+                self.state >> 1 | fb << (self.degree - 1)
+            }
+            LfsrForm::Galois => (self.state >> 1) ^ (self.mask * out),
+        };
+        out as u8
+    }
+
+    /// Produce the next ```n``` (at most 64) bits packed into a word, the first one in bit 0
+    /// # Panics
+    /// Panics if ```n``` is greater than 64
+    pub fn next_bits(&mut self, n: usize) -> u64 {
+        assert!(n <= 64, "At most 64 bits fit into a word");
+        (0..n).fold(0u64, |acc, i| acc | u64::from(self.next_bit()) << i)
+    }
+
+    /// Advance the register by ```n``` steps in ```O(L^2 log n)``` by raising its
+    /// transition matrix over GF(2) to the ```n```th power
+    pub fn jump(&mut self, n: u64) {
+        // columns of the transition matrix: the images of the unit vectors
+        let mut step = (0..self.degree)
+            .map(|i| {
+                let mut unit = Lfsr {
+                    state: 1 << i,
+                    ..self.clone()
+                };
+                unit.next_bit();
+                unit.state
+            })
+            .collect::<Vec<_>>();
+
+        let apply = |m: &[u64], v: u64| {
+            m.iter()
+                .enumerate()
+                .filter(|(i, _)| v >> i & 1 == 1)
+                .fold(0u64, |acc, (_, c)| acc ^ c)
+        };
+
+        let mut n = n;
+        while n > 0 {
+            if n & 1 == 1 {
+                self.state = apply(&step, self.state);
+            }
+            n >>= 1;
+            if n > 0 {
+                step = step.iter().map(|c| apply(&step, *c)).collect();
+            }
+        }
+    }
+}
+
+impl Iterator for Lfsr {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        Some(self.next_bit())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sequence::{self, Lfsr, LfsrForm};
+
+    #[test]
+    fn matches_generate() {
+        // x2 of the LTE sequence
+        let init = sequence::expand(0x1234_5678 & 0x7FFF_FFFF, 31);
+        let gen = |n: usize, s: &[u8]| (s[n - 28] + s[n - 29] + s[n - 30] + s[n - 31]) % 2;
+        let seq = sequence::generate(init.clone(), gen, 2000);
+
+        let fib = Lfsr::from_exponents(&[31, 3, 2, 1, 0], &init);
+        assert_eq!(fib.clone().take(2000).collect::<Vec<_>>(), seq);
+        let galois = fib.galois();
+        assert_eq!(galois.form(), LfsrForm::Galois);
+        assert_eq!(galois.take(2000).collect::<Vec<_>>(), seq);
+
+        let mask = Lfsr::from_mask(31, 0b1111, &init);
+        assert_eq!(mask.take(2000).collect::<Vec<_>>(), seq);
+    }
+
+    #[test]
+    fn period() {
+        // x^7 + x^4 + 1 is primitive: period 127
+        let init = sequence::expand(0x5A, 7);
+        for lfsr in [
+            Lfsr::from_exponents(&[7, 4, 0], &init),
+            Lfsr::from_exponents(&[0, 4, 7], &init).galois(),
+        ]
+        .iter()
+        {
+            let bits = lfsr.clone().take(254).collect::<Vec<_>>();
+            assert_eq!(bits[..127], bits[127..]);
+            assert!((1..127).all(|p| bits[..127] != bits[p..p + 127]));
+            // balanced: 64 ones
+            assert_eq!(bits[..127].iter().filter(|b| **b == 1).count(), 64);
+        }
+    }
+
+    #[test]
+    fn words_and_jumps() {
+        let init = sequence::expand(0xACE1, 16);
+        for form in &[LfsrForm::Fibonacci, LfsrForm::Galois] {
+            let lfsr = Lfsr::from_exponents(&[16, 14, 13, 11, 0], &init);
+            let lfsr = match form {
+                LfsrForm::Fibonacci => lfsr,
+                LfsrForm::Galois => lfsr.galois(),
+            };
+            let bits = lfsr.clone().take(5000).collect::<Vec<_>>();
+
+            let mut words = lfsr.clone();
+            let w = words.next_bits(64);
+            assert!((0..64).all(|i| (w >> i & 1) as u8 == bits[i]));
+            assert_eq!(
+                words.next_bits(3),
+                u64::from(bits[64] | bits[65] << 1 | bits[66] << 2)
+            );
+
+            for n in [0usize, 1, 15, 16, 17, 1000, 4321].iter() {
+                let mut jumped = lfsr.clone();
+                jumped.jump(*n as u64);
+                assert_eq!(
+                    jumped.take(100).collect::<Vec<_>>(),
+                    bits[*n..n + 100].to_vec()
+                );
+            }
+
+            // converting forms keeps the position
+            let mut l = lfsr.clone();
+            l.jump(77);
+            let l = l.fibonacci().galois().fibonacci();
+            assert_eq!(l.take(100).collect::<Vec<_>>(), bits[77..177].to_vec());
+        }
+
+        // a 64 stage register does not overflow
+        let wide = Lfsr::from_exponents(&[64, 63, 61, 60, 0], &sequence::expand(!0, 64));
+        let (mut once, mut twice) = (wide.clone(), wide.galois());
+        once.jump(1 << 40);
+        twice.jump(1 << 39);
+        twice.jump(1 << 39);
+        assert_eq!(once.next_bits(64), twice.next_bits(64));
+    }
+}
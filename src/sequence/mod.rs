@@ -1,3 +1,14 @@
+mod codes;
+mod gold;
+mod lfsr;
+mod prbs;
+mod scrambler;
+pub use self::codes::{barker, golay, ovsf, walsh, zadoff_chu};
+pub use self::gold::{gps_ca, Gold3gpp, Kasami, CA_LEN, NC};
+pub use self::lfsr::{Lfsr, LfsrForm};
+pub use self::prbs::{BerTester, Prbs, PrbsGenerator, SLIP_ERRORS, SLIP_WINDOW, SYNC_BITS};
+pub use self::scrambler::{AdditiveScrambler, MultiplicativeScrambler};
+
 /// Unpack an unsigned integer into a binary sequence of {0,1} u8  
 /// This function is provides initialisation values for M-sequence generators.  
 ///
@@ -33,6 +44,7 @@ pub fn expand(seed: u64, len: usize) -> Vec<u8> {
 /// use aether_primitives::sequence;
 /// // This example generates one half of the pseudo-random sequence
 /// // used by LTE's physical layer as per 3GPP TS36.211 7.2
+/// // (see [Gold3gpp](Gold3gpp) for the complete sequence)
 /// // It is provided in the equation form:  
 /// // ``` x1(n+31) = (x1(n+3) + x1(n)) mod2 ```
 /// // Since we use an array to generate that we need normalise the indices first.  
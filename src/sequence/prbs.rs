@@ -0,0 +1,407 @@
+use super::Lfsr;
+
+/// Pseudo-random binary sequences for bit error rate measurements (ITU-T O.150)
+///
+/// A pattern with the polynomial ```x^N + x^M + 1``` follows ```x(k) = x(k - N) + x(k - M) mod 2```.
+/// PRBS-15, PRBS-23 and PRBS-31 are transmitted inverted, so their longest run of zeros is ```N```.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prbs {
+    /// ```x^7 + x^6 + 1```
+    Prbs7,
+    /// ```x^9 + x^5 + 1``` (O.150 5.1)
+    Prbs9,
+    /// ```x^15 + x^14 + 1```, inverted (O.150 5.3)
+    Prbs15,
+    /// ```x^23 + x^18 + 1```, inverted (O.150 5.6)
+    Prbs23,
+    /// ```x^31 + x^28 + 1```, inverted (O.150 5.8)
+    Prbs31,
+}
+
+impl Prbs {
+    /// The degree ```N``` and middle exponent ```M``` of the polynomial
+    pub fn polynomial(self) -> (usize, usize) {
+        match self {
+            Prbs::Prbs7 => (7, 6),
+            Prbs::Prbs9 => (9, 5),
+            Prbs::Prbs15 => (15, 14),
+            Prbs::Prbs23 => (23, 18),
+            Prbs::Prbs31 => (31, 28),
+        }
+    }
+
+    /// Whether the register output is inverted before transmission
+    pub fn inverted(self) -> bool {
+        match self {
+            Prbs::Prbs7 | Prbs::Prbs9 => false,
+            Prbs::Prbs15 | Prbs::Prbs23 | Prbs::Prbs31 => true,
+        }
+    }
+
+    /// Period of the pattern (```2^N - 1```)
+    pub fn period(self) -> u64 {
+        (1 << self.polynomial().0) - 1
+    }
+
+    /// A generator starting from the all-ones register
+    ///
+    /// # Example
+    /// ```
+    /// use aether_primitives::sequence::Prbs;
+    ///
+    /// let bits = Prbs::Prbs9.generator().take(511 * 2).collect::<Vec<u8>>();
+    /// assert_eq!(bits[..511], bits[511..]);
+    /// ```
+    pub fn generator(self) -> PrbsGenerator {
+        let (n, _) = self.polynomial();
+        self.generator_from(&vec![1; n])
+    }
+
+    /// A generator continuing after the register contents ```x(k - N)..x(k - 1)```
+    /// (before inversion), which must not be all zeros
+    pub fn generator_from(self, init: &[u8]) -> PrbsGenerator {
+        let (n, m) = self.polynomial();
+        assert!(
+            init.iter().any(|b| b & 1 == 1),
+            "The register must not be all zeros"
+        );
+        // the register would first repeat its contents
+        let mut lfsr = Lfsr::from_exponents(&[n, n - m, 0], init).galois();
+        lfsr.jump(n as u64);
+        PrbsGenerator {
+            lfsr,
+            invert: self.inverted() as u8,
+        }
+    }
+}
+
+/// Infinite bit (```0``` or ```1```) source of a [Prbs](Prbs) pattern
+#[derive(Debug, Clone)]
+pub struct PrbsGenerator {
+    lfsr: Lfsr,
+    invert: u8,
+}
+
+impl Iterator for PrbsGenerator {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        Some(self.lfsr.next_bit() ^ self.invert)
+    }
+}
+
+/// Number of consecutive correct predictions required to declare synchronisation
+pub const SYNC_BITS: usize = 64;
+/// Length of the windows in which the error count is checked for loss of synchronisation
+pub const SLIP_WINDOW: usize = 128;
+/// Number of errors within one window which are treated as a loss of synchronisation (slip)
+pub const SLIP_ERRORS: usize = SLIP_WINDOW / 4;
+
+/// Self-synchronising bit error rate tester for a received [Prbs](Prbs) pattern
+///
+/// While hunting, each received bit is predicted from the preceding ```N``` received bits.
+/// After [SYNC_BITS](SYNC_BITS) correct predictions in a row the reference generator is
+/// loaded from the received bits and every following bit is compared against it.
+/// Errors are counted in windows of [SLIP_WINDOW](SLIP_WINDOW) bits: a window with
+/// [SLIP_ERRORS](SLIP_ERRORS) or more errors is counted as a slip (e.g. a dropped or
+/// inserted bit) and the tester starts hunting again. A window is only committed to the totals
+/// once the following one is complete, so the errors caused by a slip are discarded together
+/// with the window before it.
+///
+/// # Example
+/// ```
+/// use aether_primitives::sequence::{BerTester, Prbs};
+///
+/// let mut rx = Prbs::Prbs15.generator().skip(1234).take(100_000).collect::<Vec<u8>>();
+/// rx[50_000] ^= 1;
+/// rx[70_000] ^= 1;
+///
+/// let mut ber = BerTester::new(Prbs::Prbs15);
+/// ber.feed(&rx);
+/// assert!(ber.is_locked());
+/// assert_eq!(ber.errors(), 2);
+/// assert_eq!(ber.slips(), 0);
+/// let (low, high) = ber.interval(1.96);
+/// assert!(low < ber.ber() && ber.ber() < high);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BerTester {
+    prbs: Prbs,
+    /// received bits before inversion, x(k - 1 - i) in bit i
+    history: u64,
+    /// number of valid bits in history (saturating at N)
+    filled: usize,
+    /// consecutive correct predictions while hunting
+    matched: usize,
+    reference: Option<PrbsGenerator>,
+    window_bits: usize,
+    window_errors: usize,
+    /// the last complete window, committed after the current one
+    pending: Option<(usize, usize)>,
+    bits: u64,
+    errors: u64,
+    slips: u64,
+}
+
+impl BerTester {
+    /// Create a tester for the given pattern
+    pub fn new(prbs: Prbs) -> BerTester {
+        BerTester {
+            prbs,
+            history: 0,
+            filled: 0,
+            matched: 0,
+            reference: None,
+            window_bits: 0,
+            window_errors: 0,
+            pending: None,
+            bits: 0,
+            errors: 0,
+            slips: 0,
+        }
+    }
+
+    /// Process received bits (```0``` or ```1```)
+    pub fn feed(&mut self, bits: &[u8]) {
+        for bit in bits {
+            let bit = bit & 1;
+            match self.reference {
+                Some(ref mut reference) => {
+                    self.window_errors += usize::from(bit != reference.next().unwrap_or(0));
+                    self.window_bits += 1;
+                    if self.window_errors >= SLIP_ERRORS {
+                        self.slips += 1;
+                        self.hunt();
+                    } else if self.window_bits == SLIP_WINDOW {
+                        self.commit();
+                    }
+                }
+                None => self.track(bit),
+            }
+        }
+    }
+
+    /// hunting: predict the next bit from the received ones
+    fn track(&mut self, bit: u8) {
+        let (n, m) = self.prbs.polynomial();
+        let x = bit ^ self.prbs.inverted() as u8;
+        if self.filled == n {
+            let predicted = ((self.history >> (n - 1)) ^ (self.history >> (m - 1))) as u8 & 1;
+            self.matched = if predicted == x { self.matched + 1 } else { 0 };
+        } else {
+            self.filled += 1;
+        }
+        self.history = (self.history << 1 | u64::from(x)) & ((1 << n) - 1);
+
+        if self.matched >= SYNC_BITS {
+            let init = (0..n)
+                .map(|j| (self.history >> (n - 1 - j)) as u8 & 1)
+                .collect::<Vec<_>>();
+            self.reference = Some(self.prbs.generator_from(&init));
+        }
+    }
+
+    /// drop the current and pending windows and start hunting
+    fn hunt(&mut self) {
+        self.reference = None;
+        self.history = 0;
+        self.filled = 0;
+        self.matched = 0;
+        self.window_bits = 0;
+        self.window_errors = 0;
+        self.pending = None;
+    }
+
+    /// add the pending window to the totals, the current one becomes pending
+    fn commit(&mut self) {
+        if let Some((bits, errors)) = self.pending {
+            self.bits += bits as u64;
+            self.errors += errors as u64;
+        }
+        self.pending = Some((self.window_bits, self.window_errors));
+        self.window_bits = 0;
+        self.window_errors = 0;
+    }
+
+    /// the pending and current window
+    fn uncommitted(&self) -> (u64, u64) {
+        let (bits, errors) = self.pending.unwrap_or((0, 0));
+        (
+            (bits + self.window_bits) as u64,
+            (errors + self.window_errors) as u64,
+        )
+    }
+
+    /// Whether the tester is synchronised to the received pattern
+    pub fn is_locked(&self) -> bool {
+        self.reference.is_some()
+    }
+
+    /// Number of compared bits (including the uncommitted windows)
+    pub fn bits(&self) -> u64 {
+        self.bits + self.uncommitted().0
+    }
+
+    /// Number of bit errors (including the uncommitted windows)
+    pub fn errors(&self) -> u64 {
+        self.errors + self.uncommitted().1
+    }
+
+    /// Number of detected losses of synchronisation
+    pub fn slips(&self) -> u64 {
+        self.slips
+    }
+
+    /// Measured bit error rate (```0``` if no bits were compared yet)
+    pub fn ber(&self) -> f64 {
+        match self.bits() {
+            0 => 0.0,
+            n => self.errors() as f64 / n as f64,
+        }
+    }
+
+    /// Wilson score interval of the bit error rate for the standard normal quantile ```z```
+    /// (e.g. ```1.96``` for 95 % confidence), remains meaningful for zero errors
+    pub fn interval(&self, z: f64) -> (f64, f64) {
+        wilson(self.errors(), self.bits(), z)
+    }
+
+    /// Clear all counters and start hunting again
+    pub fn reset(&mut self) {
+        *self = BerTester::new(self.prbs);
+    }
+}
+
+/// Wilson score interval of a proportion of ```k``` in ```n``` trials
+fn wilson(k: u64, n: u64, z: f64) -> (f64, f64) {
+    if n == 0 {
+        return (0.0, 1.0);
+    }
+    let n = n as f64;
+    let p = k as f64 / n;
+    let z2 = z * z;
+    let denom = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denom;
+    let half = z / denom * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    ((center - half).max(0.0), (center + half).min(1.0))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sequence::prbs::wilson;
+    use crate::sequence::{self, BerTester, Prbs, SLIP_WINDOW, SYNC_BITS};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const ALL: [Prbs; 5] = [
+        Prbs::Prbs7,
+        Prbs::Prbs9,
+        Prbs::Prbs15,
+        Prbs::Prbs23,
+        Prbs::Prbs31,
+    ];
+
+    #[test]
+    fn patterns() {
+        for prbs in ALL.iter().take(3) {
+            let (n, m) = prbs.polynomial();
+            let period = prbs.period() as usize;
+            let bits = prbs.generator().take(2 * period).collect::<Vec<_>>();
+            assert_eq!(bits[..period], bits[period..]);
+            assert!((1..period)
+                .step_by(97)
+                .all(|p| bits[..period] != bits[p..p + period]));
+
+            // recurrence x(k) = x(k - N) + x(k - M) on the uninverted bits
+            let inv = prbs.inverted() as u8;
+            let gen = |k: usize, s: &[u8]| (s[k - n] + s[k - m]) % 2;
+            let x = sequence::generate(vec![1; n], gen, n + 1000);
+            assert!(x[n..].iter().zip(bits.iter()).all(|(x, b)| *x == b ^ inv));
+
+            // the longest run of the value which is all ones in the register
+            let run_value = 1 ^ inv;
+            let longest = bits[..period + n]
+                .split(|b| *b != run_value)
+                .map(|r| r.len())
+                .max()
+                .unwrap();
+            assert_eq!(longest, n);
+        }
+
+        // the long patterns only for their recurrence
+        for prbs in ALL.iter().skip(3) {
+            let (n, m) = prbs.polynomial();
+            let bits = prbs.generator().take(5000).collect::<Vec<_>>();
+            let gen = |k: usize, s: &[u8]| (s[k - n] + s[k - m]) % 2;
+            let x = sequence::generate(vec![1; n], gen, n + 5000);
+            assert!(x[n..].iter().zip(bits.iter()).all(|(x, b)| *x == b ^ 1));
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let mut rng = StdRng::seed_from_u64(815);
+        for prbs in ALL.iter() {
+            let mut rx = prbs.generator().skip(777).take(200_000).collect::<Vec<_>>();
+            // flip 200 bits at random positions after the synchronisation
+            let mut flipped = std::collections::HashSet::new();
+            while flipped.len() < 200 {
+                flipped.insert(rng.gen_range(1000, rx.len()));
+            }
+            flipped.iter().for_each(|i| rx[*i] ^= 1);
+
+            let mut ber = BerTester::new(*prbs);
+            // in pieces of arbitrary length
+            rx.chunks(333).for_each(|c| ber.feed(c));
+            assert!(ber.is_locked());
+            assert_eq!(ber.errors(), 200);
+            assert_eq!(ber.slips(), 0);
+            let (n, _) = prbs.polynomial();
+            assert_eq!(ber.bits(), 200_000 - (n + SYNC_BITS) as u64);
+        }
+    }
+
+    #[test]
+    fn slips() {
+        let tx = Prbs::Prbs23.generator().take(100_000).collect::<Vec<_>>();
+        // drop a bit and insert one further on
+        let mut rx = tx[..30_000].to_vec();
+        rx.extend_from_slice(&tx[30_001..60_000]);
+        rx.push(1);
+        rx.extend_from_slice(&tx[60_000..]);
+        rx[80_000] ^= 1;
+
+        let mut ber = BerTester::new(Prbs::Prbs23);
+        ber.feed(&rx);
+        assert!(ber.is_locked());
+        assert_eq!(ber.slips(), 2);
+        assert_eq!(ber.errors(), 1);
+        // the initial hunt, then at most two windows and a hunt per slip are not counted
+        let hunt = 23 + SYNC_BITS as u64;
+        assert!(ber.bits() >= 100_000 - hunt - 2 * (2 * SLIP_WINDOW as u64 + hunt));
+
+        ber.reset();
+        assert!(!ber.is_locked());
+        assert_eq!(ber.bits(), 0);
+        // random data never locks
+        let mut rng = StdRng::seed_from_u64(815);
+        let noise = (0..10_000)
+            .map(|_| rng.gen_range(0u8, 2u8))
+            .collect::<Vec<_>>();
+        ber.feed(&noise);
+        assert!(!ber.is_locked());
+    }
+
+    #[test]
+    fn interval() {
+        let (low, high) = wilson(10, 100, 1.96);
+        assert!((low - 0.05523).abs() < 1e-4);
+        assert!((high - 0.17437).abs() < 1e-4);
+
+        // no errors: the upper bound is about z^2 / n
+        let (low, high) = wilson(0, 1_000_000, 1.96);
+        assert_eq!(low, 0.0);
+        assert!((high - 3.84e-6).abs() < 1e-7);
+        assert_eq!(wilson(0, 0, 1.96), (0.0, 1.0));
+    }
+}
@@ -0,0 +1,276 @@
+use super::Lfsr;
+use crate::util::low_bits;
+
+/// Additive (synchronous) scrambler: XORs the bits with the output of an [Lfsr](Lfsr)
+/// which runs independently of the data, so scrambling and descrambling are the same operation.
+/// Both ends have to start from the same register state.
+///
+/// The register follows ```x(k) = sum_e x(k - e) mod 2``` for the exponents ```e > 0```
+/// of the polynomial. Its state persists across calls.
+///
+/// # Example
+/// ```
+/// use aether_primitives::sequence::AdditiveScrambler;
+///
+/// let data = vec![0u8, 1, 1, 0, 1, 0, 0, 0, 1, 1, 1, 0, 0, 1, 0, 1];
+/// let mut tx = AdditiveScrambler::ieee80211(0x5D);
+/// let mut rx = AdditiveScrambler::ieee80211(0x5D);
+///
+/// let mut bits = data.clone();
+/// tx.apply(&mut bits[..5]);
+/// tx.apply(&mut bits[5..]);
+/// assert_ne!(bits, data);
+/// rx.apply(&mut bits);
+/// assert_eq!(bits, data);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AdditiveScrambler {
+    lfsr: Lfsr,
+}
+
+impl AdditiveScrambler {
+    /// Create a scrambler for the polynomial given by its exponents (e.g. ```[7, 4, 0]```) whose
+    /// register holds ```x(k - N)..x(k - 1)``` (```init[N - 1]``` is the most recent bit)
+    /// # Panics
+    /// Panics if the register is all zeros
+    pub fn new(exponents: &[usize], init: &[u8]) -> AdditiveScrambler {
+        assert!(
+            init.iter().any(|b| b & 1 == 1),
+            "The register must not be all zeros"
+        );
+        let n = exponents.iter().copied().max().unwrap_or(0);
+        // x(k + N) = sum x(k + N - e) in the form of Lfsr
+        let reversed = exponents.iter().map(|e| n - e).collect::<Vec<_>>();
+        let mut lfsr = Lfsr::from_exponents(&reversed, init).galois();
+        // the register would first repeat its contents
+        lfsr.jump(n as u64);
+        AdditiveScrambler { lfsr }
+    }
+
+    /// IEEE 802.11 (OFDM PHY 17.3.5.5) scrambler ```x^7 + x^4 + 1```,
+    /// bit ```i``` of ```seed``` initialises stage ```x(i + 1)``` (the bit sent ```i + 1``` steps ago)
+    ///
+    /// With the all-ones seed it produces ```00001110 11110010 11001001 ...```
+    pub fn ieee80211(seed: u8) -> AdditiveScrambler {
+        AdditiveScrambler::new(&[7, 4, 0], &stages(u64::from(seed), 7))
+    }
+
+    /// DVB-S/DVB-C energy dispersal randomiser ```1 + x^14 + x^15``` (EN 300 421 4.4.1)
+    /// loaded with ```100101010000000``` in stages 1 to 15
+    ///
+    /// The first bytes of the sequence are ```0x03 0xF6 0x08 0x34```
+    pub fn dvb() -> AdditiveScrambler {
+        AdditiveScrambler::new(&[15, 14, 0], &stages(0b000_0000_1010_1001, 15))
+    }
+
+    /// Scramble (or descramble) bits (```0``` or ```1```) in place
+    pub fn apply(&mut self, bits: &mut [u8]) {
+        bits.iter_mut()
+            .for_each(|b| *b = (*b & 1) ^ self.lfsr.next_bit());
+    }
+}
+
+/// register contents ```x(k - N)..x(k - 1)``` where bit ```i``` of ```state``` is stage
+/// ```i + 1``` (```x(k - 1 - i)```)
+fn stages(state: u64, n: usize) -> Vec<u8> {
+    (0..n).map(|i| (state >> (n - 1 - i)) as u8 & 1).collect()
+}
+
+/// Multiplicative (self-synchronising) scrambler and descrambler for the polynomial
+/// ```x^N + ... + 1```: ```y(k) = d(k) + sum_e y(k - e) mod 2``` for the exponents ```0 < e <= N```.
+///
+/// The descrambler computes ```d(k) = y(k) + sum_e y(k - e)``` from the received bits, so it
+/// synchronises after ```N``` bits from any state, but multiplies each bit error by the number
+/// of terms of the polynomial. The state (the last ```N``` scrambled bits) persists across calls.
+///
+/// # Example
+/// ```
+/// use aether_primitives::sequence::MultiplicativeScrambler;
+///
+/// let data = vec![0u8; 200];
+/// let mut tx = MultiplicativeScrambler::ten_gbase_r();
+/// let mut bits = data.clone();
+/// tx.scramble(&mut bits);
+/// assert!(bits.iter().any(|b| *b == 1));
+///
+/// // a descrambler in a different state recovers the data after 58 bits
+/// let mut rx = MultiplicativeScrambler::ten_gbase_r().with_state(0x1234_5678);
+/// rx.descramble(&mut bits);
+/// assert_eq!(bits[58..], data[58..]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiplicativeScrambler {
+    degree: usize,
+    /// bit e - 1 set for each exponent e > 0
+    taps: u64,
+    /// scrambled bits, y(k - 1 - i) in bit i
+    state: u64,
+}
+
+impl MultiplicativeScrambler {
+    /// Create a scrambler for the polynomial given by its exponents (e.g. ```[58, 39, 0]```)
+    /// starting with the all-ones state
+    /// (which passes a stream of ones unchanged if the polynomial has an odd number of terms)
+    /// # Panics
+    /// Panics if the degree is not in ```1..=64```
+    pub fn new(exponents: &[usize]) -> MultiplicativeScrambler {
+        let degree = exponents.iter().copied().max().unwrap_or(0);
+        assert!((1..=64).contains(&degree), "The degree must be in 1..=64");
+        let taps = exponents
+            .iter()
+            .filter(|e| **e > 0)
+            .fold(0u64, |acc, e| acc | 1 << (e - 1));
+        MultiplicativeScrambler {
+            degree,
+            taps,
+            state: low_bits(degree),
+        }
+    }
+
+    /// 10GBASE-R PCS scrambler ```x^58 + x^39 + 1``` (IEEE 802.3 49.2.6)
+    pub fn ten_gbase_r() -> MultiplicativeScrambler {
+        MultiplicativeScrambler::new(&[58, 39, 0])
+    }
+
+    /// V.34 scrambler of the call modem ```x^23 + x^18 + 1``` (GPC)
+    pub fn v34_call() -> MultiplicativeScrambler {
+        MultiplicativeScrambler::new(&[23, 18, 0])
+    }
+
+    /// V.34 scrambler of the answer modem ```x^23 + x^5 + 1``` (GPA)
+    pub fn v34_answer() -> MultiplicativeScrambler {
+        MultiplicativeScrambler::new(&[23, 5, 0])
+    }
+
+    /// Set the state, bit ```i``` holds the scrambled bit sent ```i + 1``` steps ago
+    pub fn with_state(mut self, state: u64) -> MultiplicativeScrambler {
+        self.state = state & low_bits(self.degree);
+        self
+    }
+
+    /// The current state, bit ```i``` holds the scrambled bit sent ```i + 1``` steps ago
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    #[inline]
+    fn feedback(&self) -> u8 {
+        ((self.state & self.taps).count_ones() & 1) as u8
+    }
+
+    #[inline]
+    fn push(&mut self, y: u8) {
+        self.state = (self.state << 1 | u64::from(y)) & low_bits(self.degree);
+    }
+
+    /// Scramble bits (```0``` or ```1```) in place
+    pub fn scramble(&mut self, bits: &mut [u8]) {
+        for b in bits.iter_mut() {
+            let y = (*b & 1) ^ self.feedback();
+            self.push(y);
+            *b = y;
+        }
+    }
+
+    /// Descramble bits (```0``` or ```1```) in place
+    pub fn descramble(&mut self, bits: &mut [u8]) {
+        for b in bits.iter_mut() {
+            let y = *b & 1;
+            *b = y ^ self.feedback();
+            self.push(y);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bits::{self, BitOrder};
+    use crate::sequence::{AdditiveScrambler, MultiplicativeScrambler};
+    use crate::util::random_bits;
+
+    #[test]
+    fn additive() {
+        // IEEE 802.11-2016 17.3.5.5: the 127 bit sequence for the all-ones state
+        let reference = "00001110 11110010 11001001 00000010 00100110 00101110 10110110 \
+                         00001100 11010100 11100111 10110100 00101010 11111010 01010001 \
+                         10111000 1111111";
+        let reference = reference
+            .bytes()
+            .filter(|c| *c != b' ')
+            .map(|c| c - b'0')
+            .collect::<Vec<_>>();
+        assert_eq!(reference.len(), 127);
+        let mut zeros = vec![0u8; 254];
+        let mut wifi = AdditiveScrambler::ieee80211(0x7F);
+        wifi.apply(&mut zeros[..100]);
+        wifi.apply(&mut zeros[100..]);
+        assert_eq!(zeros[..127], reference[..]);
+        assert_eq!(zeros[127..], reference[..]);
+
+        // DVB: the first bytes of the PRBS (EN 300 421 Figure 2 initialisation)
+        let mut zeros = vec![0u8; 48];
+        AdditiveScrambler::dvb().apply(&mut zeros);
+        assert_eq!(
+            bits::pack(&zeros, BitOrder::MsbFirst),
+            vec![0x03, 0xF6, 0x08, 0x34, 0x30, 0xB8]
+        );
+
+        let data = random_bits(1000, 815);
+        let mut bits = data.clone();
+        AdditiveScrambler::dvb().apply(&mut bits);
+        AdditiveScrambler::dvb().apply(&mut bits);
+        assert_eq!(bits, data);
+    }
+
+    #[test]
+    fn multiplicative() {
+        let data = random_bits(5000, 815);
+        for make in &[
+            MultiplicativeScrambler::ten_gbase_r,
+            MultiplicativeScrambler::v34_call,
+            MultiplicativeScrambler::v34_answer,
+        ] {
+            let mut tx = make();
+            let mut bits = data.clone();
+            tx.scramble(&mut bits[..1234]);
+            tx.scramble(&mut bits[1234..]);
+
+            // same state: exact recovery, in arbitrary pieces
+            let mut rx = make();
+            let mut descrambled = bits.clone();
+            descrambled.chunks_mut(77).for_each(|c| rx.descramble(c));
+            assert_eq!(descrambled, data);
+            assert_eq!(rx.state(), tx.state());
+
+            // a single error is multiplied by the number of terms
+            let mut errored = bits.clone();
+            errored[2000] ^= 1;
+            make().descramble(&mut errored);
+            let errors = errored
+                .iter()
+                .zip(data.iter())
+                .filter(|(a, b)| a != b)
+                .count();
+            assert_eq!(errors, 3);
+        }
+
+        // self-synchronisation from an arbitrary state
+        let mut bits = data.clone();
+        MultiplicativeScrambler::v34_call().scramble(&mut bits);
+        let mut rx = MultiplicativeScrambler::v34_call().with_state(0x55_AAAA);
+        rx.descramble(&mut bits);
+        assert_ne!(bits[..23], data[..23]);
+        assert_eq!(bits[23..], data[23..]);
+
+        // the scrambler whitens a constant stream
+        let mut zeros = vec![0u8; 10_000];
+        MultiplicativeScrambler::ten_gbase_r().scramble(&mut zeros);
+        let count = zeros.iter().filter(|b| **b == 1).count();
+        assert!((count as i32 - 5000).abs() < 300);
+
+        // but ones are a fixed point of the all-ones state (two feedback terms)
+        let mut ones = vec![1u8; 1000];
+        MultiplicativeScrambler::ten_gbase_r().scramble(&mut ones);
+        assert!(ones.iter().all(|b| *b == 1));
+    }
+}
@@ -0,0 +1,386 @@
+use crate::cf32;
+use crate::vecops::VecOps;
+
+/// element-wise multiply ```a``` with ```b``` (```a = a * b```)
+///
+/// On x86/x86_64 the best available instruction set (AVX2 or SSE2) is detected at runtime.
+/// On other platforms or CPUs without these extensions all operations in this module
+/// fall back to the iterator-based [VecOps](crate::vecops::VecOps) versions.
+/// Neither input nor output need to be aligned, though aligned buffers load faster.
+///
+/// # Example
+/// ```
+/// use aether_primitives::{cf32, simd};
+///
+/// let mut a = vec![cf32::new(1.0, 1.0); 1023];
+/// let b = vec![cf32::new(0.0, 2.0); 1023];
+/// simd::mul(&mut a, &b);
+/// simd::conj(&mut a);
+///
+/// let mut mag = vec![0f32; 1023];
+/// simd::mag(&a, &mut mag);
+/// assert!(mag.iter().all(|m| (m - 8f32.sqrt()).abs() < 1e-6));
+/// ```
+pub fn mul(a: &mut [cf32], b: &[cf32]) {
+    assert_eq!(a.len(), b.len(), "Vectors must have same length");
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { x86::mul_avx2(a, b) };
+        } else if is_x86_feature_detected!("sse2") {
+            return unsafe { x86::mul_sse2(a, b) };
+        }
+    }
+
+    a.vec_mul(b);
+}
+
+/// element-wise add ```b``` to ```a``` (```a = a + b```)
+pub fn add(a: &mut [cf32], b: &[cf32]) {
+    assert_eq!(a.len(), b.len(), "Vectors must have same length");
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { x86::add_avx2(a, b) };
+        } else if is_x86_feature_detected!("sse2") {
+            return unsafe { x86::add_sse2(a, b) };
+        }
+    }
+
+    a.vec_add(b);
+}
+
+/// scale all elements of ```a``` with the given f32
+pub fn scale(a: &mut [cf32], scale: f32) {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { x86::scale_avx2(a, scale) };
+        } else if is_x86_feature_detected!("sse2") {
+            return unsafe { x86::scale_sse2(a, scale) };
+        }
+    }
+
+    a.vec_scale(scale);
+}
+
+/// conjugate all elements of ```a```
+pub fn conj(a: &mut [cf32]) {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { x86::conj_avx2(a) };
+        } else if is_x86_feature_detected!("sse2") {
+            return unsafe { x86::conj_sse2(a) };
+        }
+    }
+
+    a.vec_conj();
+}
+
+/// write the magnitude |x| of each element of ```src``` to ```dst```
+pub fn mag(src: &[cf32], dst: &mut [f32]) {
+    assert_eq!(src.len(), dst.len(), "Vectors must have same length");
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { x86::mag_avx2(src, dst) };
+        } else if is_x86_feature_detected!("sse2") {
+            return unsafe { x86::mag_sse2(src, dst) };
+        }
+    }
+
+    mag_scalar(src, dst);
+}
+
+/// scalar fallback for [mag](mag)
+/// uses sqrt(re^2+im^2) instead of ```norm()``` (hypot) to match the vectorised versions
+fn mag_scalar(src: &[cf32], dst: &mut [f32]) {
+    dst.iter_mut()
+        .zip(src.iter())
+        .for_each(|(d, s)| *d = s.norm_sqr().sqrt());
+}
+
+/// The actual vectorised implementations.
+/// All of these process as many elements as possible using vector instructions
+/// and hand the remainder to the scalar versions.
+/// Callers must ensure the CPU supports the enabled target feature
+/// and that the slices have the same length.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub(crate) mod x86 {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+
+    use crate::cf32;
+    use crate::vecops::VecOps;
+
+    // a cf32 is two f32s back-to-back, so a __m256 holds 4 and a __m128 holds 2 cf32s
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mul_avx2(a: &mut [cf32], b: &[cf32]) {
+        let n = a.len() / 4 * 4;
+        let pa = a.as_mut_ptr() as *mut f32;
+        let pb = b.as_ptr() as *const f32;
+        for i in (0..2 * n).step_by(8) {
+            let x = _mm256_loadu_ps(pa.add(i));
+            let y = _mm256_loadu_ps(pb.add(i));
+            // [b.re, b.re, ..] and [b.im, b.im, ..]
+            let yr = _mm256_moveldup_ps(y);
+            let yi = _mm256_movehdup_ps(y);
+            // [a.im, a.re, ..]
+            let xs = _mm256_permute_ps(x, 0xB1);
+            // [a.re*b.re - a.im*b.im, a.im*b.re + a.re*b.im, ..]
+            let r = _mm256_addsub_ps(_mm256_mul_ps(x, yr), _mm256_mul_ps(xs, yi));
+            _mm256_storeu_ps(pa.add(i), r);
+        }
+        a[n..].vec_mul(&b[n..]);
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn mul_sse2(a: &mut [cf32], b: &[cf32]) {
+        let n = a.len() / 2 * 2;
+        let pa = a.as_mut_ptr() as *mut f32;
+        let pb = b.as_ptr() as *const f32;
+        // SSE2 lacks addsub, so negate the even lanes by hand
+        let sign = _mm_set_ps(1.0, -1.0, 1.0, -1.0);
+        for i in (0..2 * n).step_by(4) {
+            let x = _mm_loadu_ps(pa.add(i));
+            let y = _mm_loadu_ps(pb.add(i));
+            let yr = _mm_shuffle_ps(y, y, 0xA0);
+            let yi = _mm_shuffle_ps(y, y, 0xF5);
+            let xs = _mm_shuffle_ps(x, x, 0xB1);
+            let r = _mm_add_ps(_mm_mul_ps(x, yr), _mm_mul_ps(_mm_mul_ps(xs, yi), sign));
+            _mm_storeu_ps(pa.add(i), r);
+        }
+        a[n..].vec_mul(&b[n..]);
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn add_avx2(a: &mut [cf32], b: &[cf32]) {
+        let n = a.len() / 4 * 4;
+        let pa = a.as_mut_ptr() as *mut f32;
+        let pb = b.as_ptr() as *const f32;
+        for i in (0..2 * n).step_by(8) {
+            let r = _mm256_add_ps(_mm256_loadu_ps(pa.add(i)), _mm256_loadu_ps(pb.add(i)));
+            _mm256_storeu_ps(pa.add(i), r);
+        }
+        a[n..].vec_add(&b[n..]);
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn add_sse2(a: &mut [cf32], b: &[cf32]) {
+        let n = a.len() / 2 * 2;
+        let pa = a.as_mut_ptr() as *mut f32;
+        let pb = b.as_ptr() as *const f32;
+        for i in (0..2 * n).step_by(4) {
+            let r = _mm_add_ps(_mm_loadu_ps(pa.add(i)), _mm_loadu_ps(pb.add(i)));
+            _mm_storeu_ps(pa.add(i), r);
+        }
+        a[n..].vec_add(&b[n..]);
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn scale_avx2(a: &mut [cf32], scale: f32) {
+        let n = a.len() / 4 * 4;
+        let pa = a.as_mut_ptr() as *mut f32;
+        let s = _mm256_set1_ps(scale);
+        for i in (0..2 * n).step_by(8) {
+            let r = _mm256_mul_ps(_mm256_loadu_ps(pa.add(i)), s);
+            _mm256_storeu_ps(pa.add(i), r);
+        }
+        a[n..].vec_scale(scale);
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn scale_sse2(a: &mut [cf32], scale: f32) {
+        let n = a.len() / 2 * 2;
+        let pa = a.as_mut_ptr() as *mut f32;
+        let s = _mm_set1_ps(scale);
+        for i in (0..2 * n).step_by(4) {
+            let r = _mm_mul_ps(_mm_loadu_ps(pa.add(i)), s);
+            _mm_storeu_ps(pa.add(i), r);
+        }
+        a[n..].vec_scale(scale);
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn conj_avx2(a: &mut [cf32]) {
+        let n = a.len() / 4 * 4;
+        let pa = a.as_mut_ptr() as *mut f32;
+        // flip the sign bit of the imaginary parts
+        let mask = _mm256_set_ps(-0.0, 0.0, -0.0, 0.0, -0.0, 0.0, -0.0, 0.0);
+        for i in (0..2 * n).step_by(8) {
+            let r = _mm256_xor_ps(_mm256_loadu_ps(pa.add(i)), mask);
+            _mm256_storeu_ps(pa.add(i), r);
+        }
+        a[n..].vec_conj();
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn conj_sse2(a: &mut [cf32]) {
+        let n = a.len() / 2 * 2;
+        let pa = a.as_mut_ptr() as *mut f32;
+        let mask = _mm_set_ps(-0.0, 0.0, -0.0, 0.0);
+        for i in (0..2 * n).step_by(4) {
+            let r = _mm_xor_ps(_mm_loadu_ps(pa.add(i)), mask);
+            _mm_storeu_ps(pa.add(i), r);
+        }
+        a[n..].vec_conj();
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mag_avx2(src: &[cf32], dst: &mut [f32]) {
+        let n = src.len() / 8 * 8;
+        let ps = src.as_ptr() as *const f32;
+        let pd = dst.as_mut_ptr();
+        for i in (0..n).step_by(8) {
+            let x1 = _mm256_loadu_ps(ps.add(2 * i));
+            let x2 = _mm256_loadu_ps(ps.add(2 * i + 8));
+            let s1 = _mm256_mul_ps(x1, x1);
+            let s2 = _mm256_mul_ps(x2, x2);
+            // shuffles work per 128bit lane: this yields |x|^2 of elements [0,1,4,5|2,3,6,7]
+            let re = _mm256_shuffle_ps(s1, s2, 0x88);
+            let im = _mm256_shuffle_ps(s1, s2, 0xDD);
+            let sum = _mm256_add_ps(re, im);
+            // restore the order by swapping the middle 64bit blocks
+            let sum = _mm256_castpd_ps(_mm256_permute4x64_pd(_mm256_castps_pd(sum), 0xD8));
+            _mm256_storeu_ps(pd.add(i), _mm256_sqrt_ps(sum));
+        }
+        super::mag_scalar(&src[n..], &mut dst[n..]);
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn mag_sse2(src: &[cf32], dst: &mut [f32]) {
+        let n = src.len() / 4 * 4;
+        let ps = src.as_ptr() as *const f32;
+        let pd = dst.as_mut_ptr();
+        for i in (0..n).step_by(4) {
+            let x1 = _mm_loadu_ps(ps.add(2 * i));
+            let x2 = _mm_loadu_ps(ps.add(2 * i + 4));
+            let s1 = _mm_mul_ps(x1, x1);
+            let s2 = _mm_mul_ps(x2, x2);
+            let re = _mm_shuffle_ps(s1, s2, 0x88);
+            let im = _mm_shuffle_ps(s1, s2, 0xDD);
+            _mm_storeu_ps(pd.add(i), _mm_sqrt_ps(_mm_add_ps(re, im)));
+        }
+        super::mag_scalar(&src[n..], &mut dst[n..]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::simd;
+    use crate::vecops::VecOps;
+    use assert_approx_eq::assert_approx_eq;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // odd lengths so the scalar remainder handling is exercised as well
+    const LENS: [usize; 4] = [0, 1, 7, 1027];
+
+    fn random(len: usize, seed: u64) -> Vec<cf32> {
+        let mut r = StdRng::seed_from_u64(seed);
+        (0..len)
+            .map(|_| cf32::new(r.gen_range(-2f32, 2f32), r.gen_range(-2f32, 2f32)))
+            .collect()
+    }
+
+    #[test]
+    fn mul() {
+        for len in LENS.iter() {
+            let (mut a, b) = (random(*len, 1), random(*len, 2));
+            let mut correct = a.clone();
+            correct.vec_mul(&b);
+            simd::mul(&mut a, &b);
+            assert_evm!(&a, &correct);
+        }
+    }
+
+    #[test]
+    fn add() {
+        for len in LENS.iter() {
+            let (mut a, b) = (random(*len, 3), random(*len, 4));
+            let mut correct = a.clone();
+            correct.vec_add(&b);
+            simd::add(&mut a, &b);
+            assert_eq!(a, correct);
+        }
+    }
+
+    #[test]
+    fn scale() {
+        for len in LENS.iter() {
+            let mut a = random(*len, 5);
+            let mut correct = a.clone();
+            correct.vec_scale(-3.5);
+            simd::scale(&mut a, -3.5);
+            assert_eq!(a, correct);
+        }
+    }
+
+    #[test]
+    fn conj() {
+        for len in LENS.iter() {
+            let mut a = random(*len, 6);
+            let mut correct = a.clone();
+            correct.vec_conj();
+            simd::conj(&mut a);
+            assert_eq!(a, correct);
+        }
+    }
+
+    #[test]
+    fn mag() {
+        for len in LENS.iter() {
+            let a = random(*len, 7);
+            let mut m = vec![0f32; *len];
+            simd::mag(&a, &mut m);
+            m.iter()
+                .zip(a.iter())
+                .for_each(|(m, a)| assert_approx_eq!(*m, a.norm(), 1e-6));
+        }
+    }
+
+    /// runtime detection picks the best path, so make sure the SSE2 versions
+    /// are checked on AVX2 machines as well
+    #[test]
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn sse2() {
+        use crate::simd::x86;
+        if !is_x86_feature_detected!("sse2") {
+            return;
+        }
+        let (a, b) = (random(1027, 8), random(1027, 9));
+
+        let (mut x, mut correct) = (a.clone(), a.clone());
+        correct.vec_mul(&b);
+        unsafe { x86::mul_sse2(&mut x, &b) };
+        assert_evm!(&x, &correct);
+
+        let (mut x, mut correct) = (a.clone(), a.clone());
+        correct.vec_add(&b);
+        unsafe { x86::add_sse2(&mut x, &b) };
+        assert_eq!(x, correct);
+
+        let (mut x, mut correct) = (a.clone(), a.clone());
+        correct.vec_scale(0.5);
+        unsafe { x86::scale_sse2(&mut x, 0.5) };
+        assert_eq!(x, correct);
+
+        let (mut x, mut correct) = (a.clone(), a.clone());
+        correct.vec_conj();
+        unsafe { x86::conj_sse2(&mut x) };
+        assert_eq!(x, correct);
+
+        let mut m = vec![0f32; a.len()];
+        unsafe { x86::mag_sse2(&a, &mut m) };
+        m.iter()
+            .zip(a.iter())
+            .for_each(|(m, a)| assert_approx_eq!(*m, a.norm(), 1e-6));
+    }
+}
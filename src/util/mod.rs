@@ -45,6 +45,24 @@ impl DB {
     }
 }
 
+/// mask of the lowest ```n``` bits
+#[inline]
+pub(crate) fn low_bits(n: usize) -> u64 {
+    if n >= 64 {
+        !0
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// Random bits (```0``` or ```1```) for tests, reproducible for a given seed
+#[cfg(test)]
+pub(crate) fn random_bits(n: usize, seed: u64) -> Vec<u8> {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    let mut r = StdRng::seed_from_u64(seed);
+    (0..n).map(|_| r.gen_range(0u8, 2u8)).collect()
+}
+
 #[cfg(test)]
 mod test {
     use crate::util::DB;
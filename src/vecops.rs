@@ -1,5 +1,9 @@
 use super::cf32;
+use crate::aligned::AlignedBuf;
+use crate::nco::Nco;
 use std::cmp;
+use std::error::Error;
+use std::fmt;
 
 #[cfg(feature = "fft")]
 use crate::fft::{Cfft, Fft, Scale};
@@ -86,243 +90,458 @@ pub trait VecOps {
     /// reuses a prebuilt fft instance
     #[cfg(feature = "fft")]
     fn vec_rifft(&mut self, fft: &mut impl Fft, scale: Scale) -> &mut Self;
-}
 
-macro_rules! impl_vec_ops {
-    ($type:ty) => {
-        impl<'a> VecOps for &'a mut $type {
-            fn vec_scale(&mut self, scale: f32) -> &mut Self {
-                self.iter_mut().for_each(|c| *c = c.scale(scale));
-                self
-            }
+    /// element-wise multiply this vector with the other one
+    /// returns an error instead of panicking if the lengths differ
+    fn try_vec_mul(&mut self, other: impl AsRef<[cf32]>) -> Result<&mut Self, LengthMismatch>
+    where
+        Self: AsRef<[cf32]>,
+    {
+        check_len(self.as_ref().len(), other.as_ref().len())?;
+        Ok(self.vec_mul(other))
+    }
 
-            fn vec_mul(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
-                assert_eq!(
-                    self.len(),
-                    other.as_ref().len(),
-                    "Vectors must have same length"
-                );
-
-                let min = cmp::min(self.len(), other.as_ref().len());
-                self[..min]
-                    .iter_mut()
-                    .zip(other.as_ref()[..min].iter())
-                    .for_each(|(a, b)| *a *= b);
-                self
-            }
+    /// element-wise divide this vector by the other one
+    /// returns an error instead of panicking if the lengths differ
+    fn try_vec_div(&mut self, other: impl AsRef<[cf32]>) -> Result<&mut Self, LengthMismatch>
+    where
+        Self: AsRef<[cf32]>,
+    {
+        check_len(self.as_ref().len(), other.as_ref().len())?;
+        Ok(self.vec_div(other))
+    }
 
-            fn vec_div(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
-                assert_eq!(
-                    self.len(),
-                    other.as_ref().len(),
-                    "Vectors must have same length"
-                );
-
-                self.iter_mut()
-                    .zip(other.as_ref().iter())
-                    .for_each(|(a, b)| *a /= b);
-                self
-            }
+    /// element-wise add the other slice to this one
+    /// returns an error instead of panicking if the lengths differ
+    fn try_vec_add(&mut self, other: impl AsRef<[cf32]>) -> Result<&mut Self, LengthMismatch>
+    where
+        Self: AsRef<[cf32]>,
+    {
+        check_len(self.as_ref().len(), other.as_ref().len())?;
+        Ok(self.vec_add(other))
+    }
 
-            fn vec_conj(&mut self) -> &mut Self {
-                self.iter_mut().for_each(|a| *a = a.conj());
-                self
-            }
+    /// element-wise subtract the other slice from this one
+    /// returns an error instead of panicking if the lengths differ
+    fn try_vec_sub(&mut self, other: impl AsRef<[cf32]>) -> Result<&mut Self, LengthMismatch>
+    where
+        Self: AsRef<[cf32]>,
+    {
+        check_len(self.as_ref().len(), other.as_ref().len())?;
+        Ok(self.vec_sub(other))
+    }
 
-            fn vec_add(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
-                assert_eq!(
-                    self.len(),
-                    other.as_ref().len(),
-                    "Vectors must have same length"
-                );
-                self.iter_mut()
-                    .zip(other.as_ref().iter())
-                    .for_each(|(a, b)| *a += b);
-                self
-            }
+    /// copies the contents of other into self
+    /// returns an error instead of panicking if the lengths differ
+    fn try_vec_clone(&mut self, other: impl AsRef<[cf32]>) -> Result<&mut Self, LengthMismatch>
+    where
+        Self: AsRef<[cf32]>,
+    {
+        check_len(self.as_ref().len(), other.as_ref().len())?;
+        Ok(self.vec_clone(other))
+    }
 
-            fn vec_sub(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
-                assert_eq!(
-                    self.len(),
-                    other.as_ref().len(),
-                    "Vectors must have same length"
-                );
-
-                self.iter_mut()
-                    .zip(other.as_ref().iter())
-                    .for_each(|(a, b)| *a -= b);
-                self
-            }
+    /// element-wise multiply this vector with the other one and write the result to ```dst```
+    /// (```dst = self * other```), leaving this vector untouched
+    fn vec_mul_into(
+        &self,
+        other: impl AsRef<[cf32]>,
+        dst: &mut [cf32],
+    ) -> Result<(), LengthMismatch>
+    where
+        Self: AsRef<[cf32]>,
+    {
+        zip_into(self.as_ref(), other.as_ref(), dst, |a, b| a * b)
+    }
 
-            fn vec_mirror(&mut self) -> &mut Self {
-                let mid = self.len() / 2;
-                (0usize..mid).for_each(|x| self.swap(x, x + mid));
-                self
-            }
+    /// element-wise divide this vector by the other one and write the result to ```dst```
+    /// (```dst = self / other```), leaving this vector untouched
+    fn vec_div_into(
+        &self,
+        other: impl AsRef<[cf32]>,
+        dst: &mut [cf32],
+    ) -> Result<(), LengthMismatch>
+    where
+        Self: AsRef<[cf32]>,
+    {
+        zip_into(self.as_ref(), other.as_ref(), dst, |a, b| a / b)
+    }
 
-            fn vec_clone(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
-                assert_eq!(
-                    self.len(),
-                    other.as_ref().len(),
-                    "Vectors must have same length"
-                );
+    /// element-wise add the other vector to this one and write the result to ```dst```
+    /// (```dst = self + other```), leaving this vector untouched
+    fn vec_add_into(
+        &self,
+        other: impl AsRef<[cf32]>,
+        dst: &mut [cf32],
+    ) -> Result<(), LengthMismatch>
+    where
+        Self: AsRef<[cf32]>,
+    {
+        zip_into(self.as_ref(), other.as_ref(), dst, |a, b| a + b)
+    }
 
-                self.copy_from_slice(other.as_ref());
-                self
-            }
+    /// element-wise subtract the other vector from this one and write the result to ```dst```
+    /// (```dst = self - other```), leaving this vector untouched
+    fn vec_sub_into(
+        &self,
+        other: impl AsRef<[cf32]>,
+        dst: &mut [cf32],
+    ) -> Result<(), LengthMismatch>
+    where
+        Self: AsRef<[cf32]>,
+    {
+        zip_into(self.as_ref(), other.as_ref(), dst, |a, b| a - b)
+    }
 
-            fn vec_zero(&mut self) -> &mut Self {
-                self.iter_mut().for_each(|c| *c = cf32 { re: 0.0, im: 0.0 });
-                self
-            }
+    /// dot product of this vector and the other one (sum of ```self[i] * other[i]```)
+    fn vec_dot(&self, other: impl AsRef<[cf32]>) -> cf32
+    where
+        Self: AsRef<[cf32]>,
+    {
+        let v = self.as_ref();
+        assert_eq!(
+            v.len(),
+            other.as_ref().len(),
+            "Vectors must have same length"
+        );
+
+        v.iter()
+            .zip(other.as_ref().iter())
+            .fold(cf32::default(), |acc, (a, b)| acc + a * b)
+    }
 
-            fn vec_mutate(&mut self, f: impl FnMut(&mut cf32)) -> &mut Self {
-                self.iter_mut().for_each(f);
-                self
-            }
+    /// conjugate dot product of this vector and the other one (sum of ```self[i] * conj(other[i])```)
+    /// i.e. the correlation of this vector with the other one at lag 0
+    fn vec_dot_conj(&self, other: impl AsRef<[cf32]>) -> cf32
+    where
+        Self: AsRef<[cf32]>,
+    {
+        let v = self.as_ref();
+        assert_eq!(
+            v.len(),
+            other.as_ref().len(),
+            "Vectors must have same length"
+        );
+
+        v.iter()
+            .zip(other.as_ref().iter())
+            .fold(cf32::default(), |acc, (a, b)| acc + a * b.conj())
+    }
 
-            #[cfg(feature = "fft")]
-            fn vec_fft(&mut self, scale: Scale) -> &mut Self {
-                let mut fft = Cfft::with_len(self.len());
-                fft.ifwd(&mut self[..], scale);
-                self
-            }
+    /// write the magnitude |x| of each element to ```dst```
+    fn vec_mag(&self, dst: &mut [f32])
+    where
+        Self: AsRef<[cf32]>,
+    {
+        let v = self.as_ref();
+        assert_eq!(v.len(), dst.len(), "Vectors must have same length");
+        dst.iter_mut()
+            .zip(v.iter())
+            .for_each(|(d, c)| *d = c.norm());
+    }
 
-            #[cfg(feature = "fft")]
-            fn vec_ifft(&mut self, scale: Scale) -> &mut Self {
-                let mut fft = Cfft::with_len(self.len());
-                fft.ibwd(&mut self[..], scale);
-                self
-            }
+    /// write the phase (argument) of each element in radians (-pi..=pi) to ```dst```
+    fn vec_arg(&self, dst: &mut [f32])
+    where
+        Self: AsRef<[cf32]>,
+    {
+        let v = self.as_ref();
+        assert_eq!(v.len(), dst.len(), "Vectors must have same length");
+        dst.iter_mut().zip(v.iter()).for_each(|(d, c)| *d = c.arg());
+    }
 
-            #[cfg(feature = "fft")]
-            fn vec_rfft(&mut self, fft: &mut impl Fft, scale: Scale) -> &mut Self {
-                fft.ifwd(self.as_mut(), scale);
-                self
-            }
-            #[cfg(feature = "fft")]
-            fn vec_rifft(&mut self, fft: &mut impl Fft, scale: Scale) -> &mut Self {
-                fft.ibwd(self.as_mut(), scale);
-                self
-            }
+    /// write the power (absolute square) |x|^2 of each element to ```dst```
+    fn vec_pow(&self, dst: &mut [f32])
+    where
+        Self: AsRef<[cf32]>,
+    {
+        let v = self.as_ref();
+        assert_eq!(v.len(), dst.len(), "Vectors must have same length");
+        dst.iter_mut()
+            .zip(v.iter())
+            .for_each(|(d, c)| *d = c.norm_sqr());
+    }
+
+    /// element-wise multiply this vector with the complex conjugate of the other one
+    fn vec_mul_conj(&mut self, other: impl AsRef<[cf32]>) -> &mut Self
+    where
+        Self: AsMut<[cf32]>,
+    {
+        let v = self.as_mut();
+        assert_eq!(
+            v.len(),
+            other.as_ref().len(),
+            "Vectors must have same length"
+        );
+
+        v.iter_mut()
+            .zip(other.as_ref().iter())
+            .for_each(|(a, b)| *a *= b.conj());
+        self
+    }
+
+    /// multiply-accumulate: element-wise add the product of ```a``` and ```b``` to this vector
+    fn vec_mac(&mut self, a: impl AsRef<[cf32]>, b: impl AsRef<[cf32]>) -> &mut Self
+    where
+        Self: AsMut<[cf32]>,
+    {
+        let v = self.as_mut();
+        assert_eq!(v.len(), a.as_ref().len(), "Vectors must have same length");
+        assert_eq!(v.len(), b.as_ref().len(), "Vectors must have same length");
+
+        v.iter_mut()
+            .zip(a.as_ref().iter().zip(b.as_ref().iter()))
+            .for_each(|(acc, (a, b))| *acc += a * b);
+        self
+    }
+
+    /// replace each element with the sum of all elements up to and including it
+    fn vec_cumsum(&mut self) -> &mut Self {
+        let mut sum = cf32::default();
+        self.vec_mutate(|c| {
+            sum += *c;
+            *c = sum;
+        })
+    }
+
+    /// exponential moving average ```y[n] = alpha * x[n] + (1 - alpha) * y[n-1]```  
+    /// ```state``` holds ```y[n-1]``` and is updated with the last output,
+    /// so successive blocks can be averaged seamlessly
+    fn vec_ema(&mut self, alpha: f32, state: &mut cf32) -> &mut Self {
+        let mut prev = *state;
+        self.vec_mutate(|c| {
+            prev = c.scale(alpha) + prev.scale(1.0 - alpha);
+            *c = prev;
+        });
+        *state = prev;
+        self
+    }
+
+    /// cyclically rotate the elements by ```k``` positions
+    /// (towards higher indices if ```k``` is positive, towards lower ones otherwise)  
+    /// In contrast to [vec_mirror](VecOps::vec_mirror) this works for any length;
+    /// rotating an even-length vector by half its length is the same as mirroring it.
+    fn vec_rotate(&mut self, k: isize) -> &mut Self
+    where
+        Self: AsMut<[cf32]>,
+    {
+        let v = self.as_mut();
+        if !v.is_empty() {
+            let len = v.len() as isize;
+            // normalise to a right rotation by 0..len
+            let k = ((k % len) + len) % len;
+            v.rotate_right(k as usize);
         }
+        self
+    }
 
-        impl<'a> VecOps for $type {
-            fn vec_scale(&mut self, scale: f32) -> &mut Self {
-                self.iter_mut().for_each(|c| *c = c.scale(scale));
-                self
-            }
+    /// shift the frequency of this vector by ```freq_hz``` (may be negative)
+    /// by mixing it with a complex tone generated by an [Nco](crate::nco::Nco)  
+    /// ```phase``` is the phase (in radians) of the tone at the first element and is updated
+    /// to the phase following the last element, so successive blocks are shifted phase-continuously
+    fn vec_freq_shift(&mut self, freq_hz: f32, sample_rate: f32, phase: &mut f32) -> &mut Self
+    where
+        Self: AsMut<[cf32]>,
+    {
+        let mut nco = Nco::new(freq_hz, sample_rate);
+        nco.set_phase(*phase);
+        nco.mix(self.as_mut());
+        *phase = nco.phase();
+        self
+    }
+}
 
-            fn vec_mul(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
-                assert_eq!(
-                    self.len(),
-                    other.as_ref().len(),
-                    "Vectors must have same length"
-                );
-
-                let min = cmp::min(self.len(), other.as_ref().len());
-                self[..min]
-                    .iter_mut()
-                    .zip(other.as_ref()[..min].iter())
-                    .for_each(|(a, b)| *a *= b);
-                self
-            }
+/// Error returned by the fallible (```try_vec_*```) and out-of-place (```vec_*_into```)
+/// variants of [VecOps](VecOps) if the lengths of the operands do not match up.
+///
+/// # Example
+/// ```
+/// use aether_primitives::{cf32, vecops::{LengthMismatch, VecOps}};
+///
+/// let a = vec![cf32::new(1.0, 1.0); 4];
+/// let b = vec![cf32::new(0.0, 2.0); 4];
+/// let mut dst = vec![cf32::default(); 4];
+/// a.vec_mul_into(&b, &mut dst).expect("All operands have the same length");
+/// assert_eq!(dst[0], cf32::new(-2.0, 2.0));
+///
+/// // a malformed block is reported instead of crashing the process
+/// let mut short = vec![cf32::default(); 3];
+/// let res = short.try_vec_add(&a).map(|_| ());
+/// assert_eq!(res, Err(LengthMismatch { expected: 3, actual: 4 }));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthMismatch {
+    /// length of the vector the operation was called on
+    pub expected: usize,
+    /// length of the offending operand
+    pub actual: usize,
+}
 
-            fn vec_div(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
-                assert_eq!(
-                    self.len(),
-                    other.as_ref().len(),
-                    "Vectors must have same length"
-                );
-
-                self.iter_mut()
-                    .zip(other.as_ref().iter())
-                    .for_each(|(a, b)| *a /= b);
-                self
-            }
+impl fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Vectors must have same length (expected {}, got {})",
+            self.expected, self.actual
+        )
+    }
+}
 
-            fn vec_conj(&mut self) -> &mut Self {
-                self.iter_mut().for_each(|a| *a = a.conj());
-                self
-            }
+impl Error for LengthMismatch {}
 
-            fn vec_add(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
-                assert_eq!(
-                    self.len(),
-                    other.as_ref().len(),
-                    "Vectors must have same length"
-                );
-                self.iter_mut()
-                    .zip(other.as_ref().iter())
-                    .for_each(|(a, b)| *a += b);
-                self
-            }
+#[inline]
+fn check_len(expected: usize, actual: usize) -> Result<(), LengthMismatch> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(LengthMismatch { expected, actual })
+    }
+}
 
-            fn vec_sub(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
-                assert_eq!(
-                    self.len(),
-                    other.as_ref().len(),
-                    "Vectors must have same length"
-                );
-
-                self.iter_mut()
-                    .zip(other.as_ref().iter())
-                    .for_each(|(a, b)| *a -= b);
-                self
-            }
+/// applies ```op``` to the element pairs of ```a``` and ```b``` and stores the results in ```dst```
+#[inline]
+fn zip_into(
+    a: &[cf32],
+    b: &[cf32],
+    dst: &mut [cf32],
+    op: impl Fn(cf32, cf32) -> cf32,
+) -> Result<(), LengthMismatch> {
+    check_len(a.len(), b.len())?;
+    check_len(a.len(), dst.len())?;
+    dst.iter_mut()
+        .zip(a.iter().zip(b.iter()))
+        .for_each(|(d, (a, b))| *d = op(*a, *b));
+    Ok(())
+}
 
-            fn vec_mirror(&mut self) -> &mut Self {
-                let mid = self.len() / 2;
-                (0usize..mid).for_each(|x| self.swap(x, x + mid));
-                self
-            }
+// The function bodies are shared by the impls for the owned type and mutable references to it
+macro_rules! vec_ops_body {
+    () => {
+        fn vec_scale(&mut self, scale: f32) -> &mut Self {
+            self.iter_mut().for_each(|c| *c = c.scale(scale));
+            self
+        }
 
-            fn vec_clone(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
-                assert_eq!(
-                    self.len(),
-                    other.as_ref().len(),
-                    "Vectors must have same length"
-                );
+        fn vec_mul(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+            assert_eq!(
+                self.len(),
+                other.as_ref().len(),
+                "Vectors must have same length"
+            );
+
+            let min = cmp::min(self.len(), other.as_ref().len());
+            self[..min]
+                .iter_mut()
+                .zip(other.as_ref()[..min].iter())
+                .for_each(|(a, b)| *a *= b);
+            self
+        }
 
-                self.copy_from_slice(other.as_ref());
-                self
-            }
+        fn vec_div(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+            assert_eq!(
+                self.len(),
+                other.as_ref().len(),
+                "Vectors must have same length"
+            );
+
+            self.iter_mut()
+                .zip(other.as_ref().iter())
+                .for_each(|(a, b)| *a /= b);
+            self
+        }
 
-            fn vec_zero(&mut self) -> &mut Self {
-                self.iter_mut().for_each(|c| *c = cf32 { re: 0.0, im: 0.0 });
-                self
-            }
+        fn vec_conj(&mut self) -> &mut Self {
+            self.iter_mut().for_each(|a| *a = a.conj());
+            self
+        }
 
-            fn vec_mutate(&mut self, f: impl FnMut(&mut cf32)) -> &mut Self {
-                self.iter_mut().for_each(f);
-                self
-            }
+        fn vec_add(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+            assert_eq!(
+                self.len(),
+                other.as_ref().len(),
+                "Vectors must have same length"
+            );
+            self.iter_mut()
+                .zip(other.as_ref().iter())
+                .for_each(|(a, b)| *a += b);
+            self
+        }
 
-            #[cfg(feature = "fft")]
-            fn vec_fft(&mut self, scale: Scale) -> &mut Self {
-                let mut fft = Cfft::with_len(self.len());
-                fft.ifwd(&mut self[..], scale);
-                self
-            }
+        fn vec_sub(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+            assert_eq!(
+                self.len(),
+                other.as_ref().len(),
+                "Vectors must have same length"
+            );
+
+            self.iter_mut()
+                .zip(other.as_ref().iter())
+                .for_each(|(a, b)| *a -= b);
+            self
+        }
 
-            #[cfg(feature = "fft")]
-            fn vec_ifft(&mut self, scale: Scale) -> &mut Self {
-                let mut fft = Cfft::with_len(self.len());
-                fft.ibwd(&mut self[..], scale);
-                self
-            }
+        fn vec_mirror(&mut self) -> &mut Self {
+            let mid = self.len() / 2;
+            (0usize..mid).for_each(|x| self.swap(x, x + mid));
+            self
+        }
 
-            #[cfg(feature = "fft")]
-            fn vec_rfft(&mut self, fft: &mut impl Fft, scale: Scale) -> &mut Self {
-                fft.ifwd(self.as_mut(), scale);
-                self
-            }
+        fn vec_clone(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+            assert_eq!(
+                self.len(),
+                other.as_ref().len(),
+                "Vectors must have same length"
+            );
 
-            #[cfg(feature = "fft")]
-            fn vec_rifft(&mut self, fft: &mut impl Fft, scale: Scale) -> &mut Self {
-                fft.ibwd(self.as_mut(), scale);
-                self
-            }
+            self.copy_from_slice(other.as_ref());
+            self
+        }
+
+        fn vec_zero(&mut self) -> &mut Self {
+            self.iter_mut().for_each(|c| *c = cf32 { re: 0.0, im: 0.0 });
+            self
+        }
+
+        fn vec_mutate(&mut self, f: impl FnMut(&mut cf32)) -> &mut Self {
+            self.iter_mut().for_each(f);
+            self
+        }
+
+        #[cfg(feature = "fft")]
+        fn vec_fft(&mut self, scale: Scale) -> &mut Self {
+            let mut fft = Cfft::with_len(self.len());
+            fft.ifwd(&mut self[..], scale);
+            self
+        }
+
+        #[cfg(feature = "fft")]
+        fn vec_ifft(&mut self, scale: Scale) -> &mut Self {
+            let mut fft = Cfft::with_len(self.len());
+            fft.ibwd(&mut self[..], scale);
+            self
+        }
+
+        #[cfg(feature = "fft")]
+        fn vec_rfft(&mut self, fft: &mut impl Fft, scale: Scale) -> &mut Self {
+            fft.ifwd(self.as_mut(), scale);
+            self
+        }
+        #[cfg(feature = "fft")]
+        fn vec_rifft(&mut self, fft: &mut impl Fft, scale: Scale) -> &mut Self {
+            fft.ibwd(self.as_mut(), scale);
+            self
+        }
+    };
+}
+
+macro_rules! impl_vec_ops {
+    ($type:ty) => {
+        impl<'a> VecOps for &'a mut $type {
+            vec_ops_body!();
+        }
+
+        impl<'a> VecOps for $type {
+            vec_ops_body!();
         }
     };
 }
@@ -330,6 +549,7 @@ macro_rules! impl_vec_ops {
 // derive using the macro above
 impl_vec_ops!([cf32]);
 impl_vec_ops!(Vec<cf32>);
+impl_vec_ops!(AlignedBuf<cf32>);
 
 #[cfg(test)]
 mod test {
@@ -440,6 +660,319 @@ mod test {
         assert_evm!(&v, &linear);
     }
 
+    #[test]
+    fn try_vec_ops() {
+        use crate::vecops::LengthMismatch;
+        let mut v = vec![cf32::new(2.0, 2.0); 100];
+        let ones = vec![cf32::new(1.0, 1.0); 100];
+        let short = vec![cf32::new(1.0, 1.0); 99];
+
+        v.try_vec_sub(&ones)
+            .and_then(|v| v.try_vec_mul(&ones))
+            .and_then(|v| v.try_vec_add(&ones))
+            .and_then(|v| v.try_vec_div(&ones))
+            .expect("Lengths match up");
+        // (2+2j - (1+1j)) * (1+1j) + (1+1j) = 1+3j; divided by 1+1j = 2+1j
+        assert_evm!(&v, vec![cf32::new(2.0, 1.0); 100]);
+
+        let err = Err(LengthMismatch {
+            expected: 100,
+            actual: 99,
+        });
+        assert_eq!(v.try_vec_mul(&short).map(|_| ()), err);
+        assert_eq!(v.try_vec_div(&short).map(|_| ()), err);
+        assert_eq!(v.try_vec_add(&short).map(|_| ()), err);
+        assert_eq!(v.try_vec_sub(&short).map(|_| ()), err);
+        assert_eq!(v.try_vec_clone(&short).map(|_| ()), err);
+        // a failed operation must not touch the vector
+        assert_evm!(&v, vec![cf32::new(2.0, 1.0); 100]);
+
+        v.try_vec_clone(&ones).expect("Lengths match up");
+        assert_evm!(&v, &ones);
+    }
+
+    #[test]
+    fn vec_ops_into() {
+        use crate::vecops::LengthMismatch;
+        let a = vec![cf32::new(1.0, 1.0); 100];
+        let b = vec![cf32::new(0.0, 2.0); 100];
+        let mut dst = vec![cf32::default(); 100];
+
+        a.vec_mul_into(&b, &mut dst).unwrap();
+        assert_evm!(&dst, vec![cf32::new(-2.0, 2.0); 100]);
+        a.vec_div_into(&b, &mut dst).unwrap();
+        assert_evm!(&dst, vec![cf32::new(0.5, -0.5); 100]);
+        a.vec_add_into(&b, &mut dst).unwrap();
+        assert_evm!(&dst, vec![cf32::new(1.0, 3.0); 100]);
+        a[..].vec_sub_into(&b, &mut dst).unwrap();
+        assert_evm!(&dst, vec![cf32::new(1.0, -1.0); 100]);
+        // the inputs are left untouched
+        assert_evm!(&a, vec![cf32::new(1.0, 1.0); 100]);
+
+        assert_eq!(
+            a.vec_mul_into(&b[..99], &mut dst),
+            Err(LengthMismatch {
+                expected: 100,
+                actual: 99
+            })
+        );
+        assert_eq!(
+            a.vec_add_into(&b, &mut dst[..50]),
+            Err(LengthMismatch {
+                expected: 100,
+                actual: 50
+            })
+        );
+    }
+
+    /// a downstream type which only implements the required methods of the trait
+    struct Samples(Vec<cf32>);
+
+    impl AsRef<[cf32]> for Samples {
+        fn as_ref(&self) -> &[cf32] {
+            &self.0
+        }
+    }
+
+    impl AsMut<[cf32]> for Samples {
+        fn as_mut(&mut self) -> &mut [cf32] {
+            &mut self.0
+        }
+    }
+
+    impl VecOps for Samples {
+        fn vec_scale(&mut self, scale: f32) -> &mut Self {
+            self.0.vec_scale(scale);
+            self
+        }
+        fn vec_mul(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+            self.0.vec_mul(other);
+            self
+        }
+        fn vec_div(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+            self.0.vec_div(other);
+            self
+        }
+        fn vec_conj(&mut self) -> &mut Self {
+            self.0.vec_conj();
+            self
+        }
+        fn vec_mirror(&mut self) -> &mut Self {
+            self.0.vec_mirror();
+            self
+        }
+        fn vec_clone(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+            self.0.vec_clone(other);
+            self
+        }
+        fn vec_zero(&mut self) -> &mut Self {
+            self.0.vec_zero();
+            self
+        }
+        fn vec_mutate(&mut self, f: impl FnMut(&mut cf32)) -> &mut Self {
+            self.0.vec_mutate(f);
+            self
+        }
+        fn vec_add(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+            self.0.vec_add(other);
+            self
+        }
+        fn vec_sub(&mut self, other: impl AsRef<[cf32]>) -> &mut Self {
+            self.0.vec_sub(other);
+            self
+        }
+        #[cfg(feature = "fft")]
+        fn vec_fft(&mut self, scale: crate::fft::Scale) -> &mut Self {
+            self.0.vec_fft(scale);
+            self
+        }
+        #[cfg(feature = "fft")]
+        fn vec_ifft(&mut self, scale: crate::fft::Scale) -> &mut Self {
+            self.0.vec_ifft(scale);
+            self
+        }
+        #[cfg(feature = "fft")]
+        fn vec_rfft(
+            &mut self,
+            fft: &mut impl crate::fft::Fft,
+            scale: crate::fft::Scale,
+        ) -> &mut Self {
+            self.0.vec_rfft(fft, scale);
+            self
+        }
+        #[cfg(feature = "fft")]
+        fn vec_rifft(
+            &mut self,
+            fft: &mut impl crate::fft::Fft,
+            scale: crate::fft::Scale,
+        ) -> &mut Self {
+            self.0.vec_rifft(fft, scale);
+            self
+        }
+    }
+
+    #[test]
+    fn provided_methods() {
+        use crate::vecops::LengthMismatch;
+        let ones = vec![cf32::new(1.0, 1.0); 10];
+        let mut s = Samples(vec![cf32::new(2.0, 2.0); 10]);
+
+        s.try_vec_sub(&ones)
+            .and_then(|s| s.try_vec_mul(&ones))
+            .expect("Lengths match up");
+        assert_evm!(&s.0, vec![cf32::new(0.0, 2.0); 10]);
+        assert_eq!(
+            s.try_vec_add(&ones[..9]).map(|_| ()),
+            Err(LengthMismatch {
+                expected: 10,
+                actual: 9
+            })
+        );
+
+        let mut dst = vec![cf32::default(); 10];
+        s.vec_add_into(&ones, &mut dst).unwrap();
+        assert_evm!(&dst, vec![cf32::new(1.0, 3.0); 10]);
+        assert_eq!(s.vec_dot_conj(&ones), cf32::new(20.0, 20.0));
+
+        s.vec_mul_conj(&ones).vec_cumsum().vec_rotate(1);
+        assert_eq!(s.0[0], cf32::new(20.0, 20.0));
+        assert_eq!(s.0[1], cf32::new(2.0, 2.0));
+
+        let mut phase = 0.0;
+        s.vec_zero()
+            .vec_add(&ones)
+            .vec_freq_shift(0.0, 1.0, &mut phase);
+        assert_evm!(&s.0, &ones);
+    }
+
+    fn ramp(len: usize) -> Vec<cf32> {
+        (0..len)
+            .map(|i| cf32::new(i as f32, 0.5 * len as f32 - i as f32))
+            .collect()
+    }
+
+    #[test]
+    fn vec_dot() {
+        let a = ramp(33);
+        let b = a
+            .iter()
+            .map(|c| c * cf32::new(0.0, 1.0))
+            .collect::<Vec<_>>();
+
+        let mut dot = cf32::default();
+        let mut dot_conj = cf32::default();
+        for i in 0..a.len() {
+            dot += a[i] * b[i];
+            dot_conj += a[i] * b[i].conj();
+        }
+
+        assert_evm!(&[a.vec_dot(&b)], &[dot]);
+        assert_evm!(&[a.vec_dot_conj(&b)], &[dot_conj]);
+        // the conjugate dot product of a vector with itself is its energy
+        let energy = a.iter().map(|c| c.norm_sqr()).sum::<f32>();
+        assert_evm!(&[a.vec_dot_conj(&a)], &[cf32::new(energy, 0.0)]);
+    }
+
+    #[test]
+    fn vec_mag_arg_pow() {
+        use assert_approx_eq::assert_approx_eq;
+        let a = ramp(17);
+        let mut mag = vec![0f32; 17];
+        let mut arg = vec![0f32; 17];
+        let mut pow = vec![0f32; 17];
+        a.vec_mag(&mut mag);
+        a.vec_arg(&mut arg);
+        a.vec_pow(&mut pow);
+
+        for i in 0..a.len() {
+            assert_approx_eq!(mag[i], (a[i].re * a[i].re + a[i].im * a[i].im).sqrt());
+            assert_approx_eq!(arg[i], a[i].im.atan2(a[i].re));
+            assert_approx_eq!(pow[i], a[i].re * a[i].re + a[i].im * a[i].im);
+        }
+    }
+
+    #[test]
+    fn vec_mul_conj() {
+        let mut a = ramp(20);
+        let b = vec![cf32::new(1.0, 2.0); 20];
+        let correct = a
+            .iter()
+            .map(|c| c * cf32::new(1.0, -2.0))
+            .collect::<Vec<_>>();
+
+        a.vec_mul_conj(&b);
+        assert_evm!(&a, &correct);
+    }
+
+    #[test]
+    fn vec_mac() {
+        let mut acc = vec![cf32::new(1.0, 1.0); 10];
+        let a = ramp(10);
+        let b = vec![cf32::new(0.0, 2.0); 10];
+        let correct = a
+            .iter()
+            .map(|c| cf32::new(1.0, 1.0) + c * cf32::new(0.0, 2.0))
+            .collect::<Vec<_>>();
+
+        acc.vec_mac(&a, &b);
+        assert_evm!(&acc, &correct);
+    }
+
+    #[test]
+    fn vec_cumsum() {
+        let mut v = ramp(12);
+        let mut correct = v.clone();
+        for i in 1..correct.len() {
+            correct[i] = correct[i - 1] + correct[i];
+        }
+
+        v.vec_cumsum();
+        assert_evm!(&v, &correct);
+    }
+
+    #[test]
+    fn vec_ema() {
+        let alpha = 0.25;
+        let input = ramp(40);
+        let mut correct = input.clone();
+        let mut prev = cf32::new(1.0, -1.0);
+        for c in correct.iter_mut() {
+            prev = alpha * *c + (1.0 - alpha) * prev;
+            *c = prev;
+        }
+
+        // averaging two blocks in succession must match averaging the whole vector
+        let mut state = cf32::new(1.0, -1.0);
+        let mut v = input.clone();
+        v[..15].vec_ema(alpha, &mut state);
+        v[15..].vec_ema(alpha, &mut state);
+
+        assert_evm!(&v, &correct);
+        assert_eq!(state, correct[39]);
+    }
+
+    #[test]
+    fn vec_rotate() {
+        for len in &[0usize, 1, 5, 8] {
+            let v = ramp(*len);
+            for k in -11isize..=11 {
+                let mut r = v.clone();
+                r.vec_rotate(k);
+                for (i, c) in v.iter().enumerate() {
+                    let target = (i as isize + k).rem_euclid(*len as isize) as usize;
+                    assert_eq!(r[target], *c, "Rotating length {} by {} failed", len, k);
+                }
+            }
+        }
+
+        // a rotation by len/2 is a mirror for even lengths
+        let mut a = ramp(8);
+        let mut b = a.clone();
+        a.vec_rotate(4);
+        b.vec_mirror();
+        assert_eq!(a, b);
+    }
+
     #[test]
     #[cfg(feature = "fft")]
     fn vec_fft() {
@@ -0,0 +1,224 @@
+use crate::util::DB;
+use crate::{cf32, cf64};
+
+/// Statistics of a vector or slice of samples as gathered by [VecStats](VecStats)
+/// Magnitudes are given as |x|, powers as |x|^2.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats<T> {
+    /// Index and magnitude of the element with the smallest magnitude
+    pub min: (usize, f32),
+    /// Index and magnitude of the element with the largest magnitude
+    pub max: (usize, f32),
+    /// Mean of the element magnitudes
+    pub mean: f32,
+    /// Variance of the elements around the DC offset
+    pub var: f32,
+    /// Average power (mean of the squared magnitudes)
+    pub power: f32,
+    /// Root mean square (square root of the average power)
+    pub rms: f32,
+    /// Peak-to-average power ratio (0 dB for an all-zero vector)
+    pub papr: DB,
+    /// DC offset estimate (mean of the elements)
+    pub dc: T,
+}
+
+/// Gather statistics of vectors and slices of ```cf32``` or ```f32```
+/// in a single pass over the elements.
+/// Sums are accumulated in double precision to limit rounding errors on long vectors.
+///
+/// # Example
+/// ```
+/// use aether_primitives::{cf32, vecstats::VecStats};
+///
+/// let v = vec![cf32::new(1.0, 0.0), cf32::new(0.0, 3.0), cf32::new(-2.0, 0.0)];
+/// let s = v.vec_stats();
+///
+/// assert_eq!(s.min, (0, 1.0));
+/// assert_eq!(s.max, (1, 3.0));
+/// assert_eq!(s.mean, 2.0);
+/// ```
+pub trait VecStats {
+    /// Element type of the vector; this is also the type of the DC offset estimate
+    type Elem;
+
+    /// Compute the statistics of this vector
+    /// # Panics
+    /// Panics if the vector is empty
+    fn vec_stats(&self) -> Stats<Self::Elem>;
+}
+
+/// Running sums of the magnitude-based statistics
+struct Acc {
+    min: (usize, f64),
+    max: (usize, f64),
+    sum_mag: f64,
+    sum_pow: f64,
+}
+
+impl Acc {
+    fn new() -> Acc {
+        Acc {
+            min: (0, f64::INFINITY),
+            max: (0, f64::NEG_INFINITY),
+            sum_mag: 0f64,
+            sum_pow: 0f64,
+        }
+    }
+
+    #[inline(always)]
+    fn push(&mut self, idx: usize, pow: f64) {
+        if pow < self.min.1 {
+            self.min = (idx, pow);
+        }
+        if pow > self.max.1 {
+            self.max = (idx, pow);
+        }
+        self.sum_mag += pow.sqrt();
+        self.sum_pow += pow;
+    }
+
+    /// ```dc_pow```: power of the DC offset estimate
+    fn finish<T>(self, len: usize, dc_pow: f64, dc: T) -> Stats<T> {
+        let n = len as f64;
+        let power = self.sum_pow / n;
+        Stats {
+            min: (self.min.0, self.min.1.sqrt() as f32),
+            max: (self.max.0, self.max.1.sqrt() as f32),
+            mean: (self.sum_mag / n) as f32,
+            // guard against tiny negative values caused by rounding
+            var: (power - dc_pow).max(0f64) as f32,
+            power: power as f32,
+            rms: power.sqrt() as f32,
+            // an all-zero vector has no peak above its average
+            papr: DB::from(if power > 0f64 {
+                self.max.1 / power
+            } else {
+                1f64
+            }),
+            dc,
+        }
+    }
+}
+
+impl VecStats for [cf32] {
+    type Elem = cf32;
+
+    fn vec_stats(&self) -> Stats<cf32> {
+        assert!(
+            !self.is_empty(),
+            "Cannot compute statistics of an empty vector"
+        );
+
+        let mut acc = Acc::new();
+        let mut sum = cf64::default();
+        for (idx, c) in self.iter().enumerate() {
+            let c = cf64::new(f64::from(c.re), f64::from(c.im));
+            acc.push(idx, c.norm_sqr());
+            sum += c;
+        }
+
+        let dc = sum / self.len() as f64;
+        acc.finish(
+            self.len(),
+            dc.norm_sqr(),
+            cf32::new(dc.re as f32, dc.im as f32),
+        )
+    }
+}
+
+impl VecStats for [f32] {
+    type Elem = f32;
+
+    fn vec_stats(&self) -> Stats<f32> {
+        assert!(
+            !self.is_empty(),
+            "Cannot compute statistics of an empty vector"
+        );
+
+        let mut acc = Acc::new();
+        let mut sum = 0f64;
+        for (idx, x) in self.iter().enumerate() {
+            let x = f64::from(*x);
+            acc.push(idx, x * x);
+            sum += x;
+        }
+
+        let dc = sum / self.len() as f64;
+        acc.finish(self.len(), dc * dc, dc as f32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cf32;
+    use crate::vecstats::VecStats;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn cf32_stats() {
+        let v = [
+            cf32::new(1.0, 1.0),
+            cf32::new(0.0, 0.5),
+            cf32::new(-3.0, 0.0),
+            cf32::new(2.0, 1.5),
+        ];
+        let s = v[..].vec_stats();
+
+        assert_eq!(s.min, (1, 0.5));
+        assert_eq!(s.max, (2, 3.0));
+        assert_approx_eq!(s.mean, (2f32.sqrt() + 0.5 + 3.0 + 2.5) / 4.0);
+        // powers: 2, 0.25, 9, 6.25
+        assert_approx_eq!(s.power, 17.5 / 4.0);
+        assert_approx_eq!(s.rms, (17.5f32 / 4.0).sqrt());
+        assert_approx_eq!(s.papr.ratio(), 9.0 / (17.5 / 4.0));
+        assert_evm!(&[s.dc], &[cf32::new(0.0, 0.75)]);
+        assert_approx_eq!(s.var, 17.5 / 4.0 - 0.75 * 0.75);
+    }
+
+    #[test]
+    fn f32_stats() {
+        let v = [1f32, -1.0, 1.0, -1.0, 4.0];
+        let s = v[..].vec_stats();
+
+        assert_eq!(s.min, (0, 1.0));
+        assert_eq!(s.max, (4, 4.0));
+        assert_approx_eq!(s.mean, 8.0 / 5.0);
+        assert_approx_eq!(s.dc, 4.0 / 5.0);
+        assert_approx_eq!(s.power, 20.0 / 5.0);
+        assert_approx_eq!(s.var, 4.0 - 0.64);
+        assert_approx_eq!(s.papr.db(), 6.020599913279624);
+    }
+
+    #[test]
+    fn constant_envelope() {
+        // a tone has constant magnitude, no DC and a PAPR of 0 dB
+        let v = (0..64)
+            .map(|i| cf32::from_polar(&2.0, &(i as f32 * std::f32::consts::PI / 8.0)))
+            .collect::<Vec<_>>();
+        let s = v[..].vec_stats();
+
+        assert_approx_eq!(s.power, 4.0, 1e-5);
+        assert_approx_eq!(s.var, 4.0, 1e-5);
+        assert_approx_eq!(s.papr.db() as f32, 0.0, 1e-5);
+        assert_approx_eq!(s.dc.norm(), 0.0, 1e-5);
+    }
+
+    #[test]
+    fn zeros() {
+        let s = [cf32::default(); 16][..].vec_stats();
+        assert_eq!(s.power, 0.0);
+        assert_eq!(s.var, 0.0);
+        assert_eq!(s.papr.db(), 0.0);
+
+        let s = [0f32; 16][..].vec_stats();
+        assert_eq!(s.papr.db(), 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty() {
+        let v: Vec<f32> = vec![];
+        v.vec_stats();
+    }
+}